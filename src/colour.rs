@@ -0,0 +1,119 @@
+// ANSI colour for the presenter, only when whoever is reading can see it
+
+use theme::{Depth, Theme};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColourChoice {
+    Always,
+    Never,
+    Auto,
+}
+
+// what a piece of output is, so it can be styled consistently wherever it appears
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Role {
+    Header,
+    Name,
+    Family,
+    Sureness,
+    Error,
+    Gutter,
+    Key,
+    String,
+    Number,
+    Boolean,
+    Null,
+    Magic,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Palette {
+    theme: Option<Theme>,
+    depth: Depth,
+}
+
+pub fn parse_choice(arg: &str) -> Result<ColourChoice, String> {
+    match arg {
+        "always" => Ok(ColourChoice::Always),
+        "never" => Ok(ColourChoice::Never),
+        "auto" => Ok(ColourChoice::Auto),
+        _ => Err(format!("colour must be 'always', 'never' or 'auto', got '{}'", arg)),
+    }
+}
+
+// auto means a terminal is listening and nobody has asked for no colour (https://no-color.org)
+pub fn enabled(choice: ColourChoice, is_terminal: bool, no_color: Option<&str>) -> bool {
+    match choice {
+        ColourChoice::Always => true,
+        ColourChoice::Never => false,
+        ColourChoice::Auto => is_terminal && no_color.map(|v| v.is_empty()).unwrap_or(true),
+    }
+}
+
+impl Palette {
+    pub fn plain() -> Self {
+        Palette { theme: None, depth: Depth::Sixteen }
+    }
+
+    // the default theme in the 16 colours every terminal has
+    #[cfg(test)]
+    pub fn ansi() -> Self {
+        Palette::new(Theme::default(), Depth::Sixteen)
+    }
+
+    pub fn new(theme: Theme, depth: Depth) -> Self {
+        Palette { theme: Some(theme), depth }
+    }
+
+    pub fn paint(&self, role: Role, text: &str) -> String {
+        let sgr = match self.theme {
+            Some(ref theme) => theme.style(role).sgr(self.depth),
+            None => String::new(),
+        };
+
+        if sgr.is_empty() || text.is_empty() {
+            return text.to_owned();
+        }
+
+        format!("\x1b[{}m{}\x1b[0m", sgr, text)
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::plain()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choices() {
+        assert_eq!(Ok(ColourChoice::Always), parse_choice("always"));
+        assert_eq!(Ok(ColourChoice::Never), parse_choice("never"));
+        assert_eq!(Ok(ColourChoice::Auto), parse_choice("auto"));
+        assert_eq!(Err("colour must be 'always', 'never' or 'auto', got 'rainbow'".to_owned()), parse_choice("rainbow"));
+    }
+
+    #[test]
+    fn auto_detection() {
+        assert!(enabled(ColourChoice::Auto, true, None));
+        assert!(enabled(ColourChoice::Auto, true, Some("")));
+        assert!(!enabled(ColourChoice::Auto, false, None));
+        assert!(!enabled(ColourChoice::Auto, true, Some("1")));
+
+        assert!(enabled(ColourChoice::Always, false, Some("1")));
+        assert!(!enabled(ColourChoice::Never, true, None));
+    }
+
+    #[test]
+    fn painting() {
+        assert_eq!("hello", Palette::plain().paint(Role::Error, "hello"));
+        assert_eq!("\x1b[1;31mhello\x1b[0m", Palette::ansi().paint(Role::Error, "hello"));
+        assert_eq!("", Palette::ansi().paint(Role::Error, ""));
+        assert_eq!("\x1b[1;38;5;160mhello\x1b[0m", Palette::new(Theme::light(), Depth::TwoFiftySix).paint(Role::Error, "hello"));
+        assert_eq!("\x1b[1;31mhello\x1b[0m", Palette::new(Theme::light(), Depth::Sixteen).paint(Role::Error, "hello"));
+    }
+}
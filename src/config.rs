@@ -0,0 +1,145 @@
+// settings read from a TOML config file, e.g. ~/.config/scrutey/config.toml
+//
+//   theme = "mine"          # default, light, dark or one of the themes below
+//   colours = "256"         # 16, 256, truecolour or auto
+//
+//   [themes.mine]
+//   base = "light"          # built in theme to start from, default if missing
+//   key = "#005f87"
+//   error = "bold red"
+//
+// every role can be styled: header, name, family, sureness, error, gutter, key, string, number, boolean, null and magic
+
+use colour::Role;
+use theme::{self, Depth, Style, Theme};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use toml::{Table, Value};
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Config {
+    pub theme: Option<String>,
+    pub depth: Option<Depth>,
+    pub themes: HashMap<String, Theme>,
+}
+
+fn as_str<'a>(value: &'a Value, setting: &str) -> Result<&'a str, String> {
+    value.as_str().ok_or_else(|| format!("'{}' should be a string", setting))
+}
+
+fn parse_theme(name: &str, table: &Table) -> Result<Theme, String> {
+    let base = match table.get("base") {
+        Some(base) => as_str(base, &format!("themes.{}.base", name))?,
+        None => "default",
+    };
+
+    let mut theme = Theme::built_in(base)
+        .ok_or_else(|| format!("theme '{}' is based on '{}', which isn't one of {}", name, base, theme::BUILT_IN.join(", ")))?;
+
+    for (key, value) in table.iter().filter(|&(k, _)| k != "base") {
+        let setting = format!("themes.{}.{}", name, key);
+        let role = Role::from_name(key).ok_or_else(|| format!("'{}' isn't something that can be coloured", setting))?;
+        let style = Style::parse(as_str(value, &setting)?).map_err(|e| format!("'{}' - {}", setting, e))?;
+        theme.set(role, style);
+    }
+
+    Ok(theme)
+}
+
+pub fn parse(text: &str) -> Result<Config, String> {
+    let table: Table = text.parse().map_err(|e: toml::de::Error| e.message().to_owned())?;
+    let mut config = Config::default();
+
+    for (key, value) in &table {
+        match key.as_str() {
+            "theme" => config.theme = Some(as_str(value, key)?.to_owned()),
+            "colours" | "colors" => config.depth = theme::parse_depth(as_str(value, key)?)?,
+            "themes" => {
+                let themes = value.as_table().ok_or("'themes' should be a table of themes")?;
+
+                for (name, theme) in themes {
+                    let theme = theme.as_table().ok_or_else(|| format!("'themes.{}' should be a table", name))?;
+                    config.themes.insert(name.to_owned(), parse_theme(name, theme)?);
+                }
+            }
+            other => return Err(format!("unknown setting '{}'", other)),
+        }
+    }
+
+    Ok(config)
+}
+
+// $XDG_CONFIG_HOME/scrutey/config.toml, falling back to ~/.config
+pub fn default_path(xdg_config_home: Option<String>, home: Option<String>) -> Option<PathBuf> {
+    let base = match (xdg_config_home, home) {
+        (Some(xdg), _) if !xdg.is_empty() => PathBuf::from(xdg),
+        (_, Some(home)) if !home.is_empty() => PathBuf::from(home).join(".config"),
+        _ => return None,
+    };
+
+    Some(base.join("scrutey").join("config.toml"))
+}
+
+impl Config {
+    // the named theme, or the one chosen in the config file, or the default
+    pub fn theme(&self, name: Option<&str>) -> Result<Theme, String> {
+        let name = name.or(self.theme.as_deref()).unwrap_or("default");
+
+        self.themes
+            .get(name)
+            .cloned()
+            .or_else(|| Theme::built_in(name))
+            .ok_or_else(|| format!("unknown theme '{}', expected {} or one defined in the config file",
+                                   name, theme::BUILT_IN.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::Colour;
+
+    #[test]
+    fn empty() {
+        assert_eq!(Ok(Config::default()), parse(""));
+        assert_eq!(Ok(Theme::default()), Config::default().theme(None));
+    }
+
+    #[test]
+    fn custom_theme() {
+        let config = parse("theme = \"mine\"\n\
+                            colours = \"truecolour\"\n\
+                            [themes.mine]\n\
+                            base = \"light\"\n\
+                            key = \"bold #005f87\"\n").expect("should parse");
+
+        assert_eq!(Some(Depth::TrueColour), config.depth);
+
+        let theme = config.theme(None).expect("theme should exist");
+        assert_eq!(Style { bold: true, colour: Some(Colour::Rgb(0, 95, 135)) }, theme.style(Role::Key));
+        assert_eq!(Theme::light().style(Role::String), theme.style(Role::String));
+
+        assert_eq!(Ok(Theme::dark()), config.theme(Some("dark")));
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        assert_eq!(Err("unknown setting 'colour'".to_owned()), parse("colour = \"256\""));
+        assert_eq!(Err("'themes.mine.keys' isn't something that can be coloured".to_owned()),
+                   parse("[themes.mine]\nkeys = \"red\""));
+        assert_eq!(Err("theme 'mine' is based on 'sepia', which isn't one of default, light, dark".to_owned()),
+                   parse("[themes.mine]\nbase = \"sepia\""));
+        assert!(parse("[themes.mine]\nkey = \"puce\"").is_err());
+        assert!(parse("theme = ").is_err());
+
+        assert_eq!(Err("unknown theme 'neon', expected default, light, dark or one defined in the config file".to_owned()),
+                   Config::default().theme(Some("neon")));
+    }
+
+    #[test]
+    fn default_paths() {
+        assert_eq!(Some(PathBuf::from("/xdg/scrutey/config.toml")), default_path(Some("/xdg".into()), Some("/home/me".into())));
+        assert_eq!(Some(PathBuf::from("/home/me/.config/scrutey/config.toml")), default_path(None, Some("/home/me".into())));
+        assert_eq!(None, default_path(None, None));
+    }
+}
@@ -0,0 +1,115 @@
+// inflate compressed bytes, stopping at a limit so a small zip bomb can't eat all the memory
+
+use families::Family;
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use bzip2::read::MultiBzDecoder;
+use lz4_flex::frame::FrameDecoder;
+use std::io::Read;
+
+pub const LIMIT: u64 = 32 * 1024 * 1024;
+
+pub fn algorithm(family: Family) -> Option<&'static str> {
+    match family {
+        Family::GZIP => Some("gzip"),
+        Family::ZLIB => Some("zlib"),
+        Family::DEFLATE => Some("deflate"),
+        Family::BZIP2 => Some("bzip2"),
+        Family::ZSTD => Some("zstd"),
+        Family::LZ4 => Some("lz4"),
+        _ => None,
+    }
+}
+
+fn decoder<'a>(family: Family, bytes: &'a [u8]) -> Result<Box<dyn Read + 'a>, String> {
+    match family {
+        Family::GZIP => Ok(Box::new(MultiGzDecoder::new(bytes))),
+        Family::ZLIB => Ok(Box::new(ZlibDecoder::new(bytes))),
+        Family::DEFLATE => Ok(Box::new(DeflateDecoder::new(bytes))),
+        Family::BZIP2 => Ok(Box::new(MultiBzDecoder::new(bytes))),
+        Family::ZSTD => zstd::stream::read::Decoder::new(bytes)
+                            .map(|d| Box::new(d) as Box<dyn Read>)
+                            .map_err(|e| e.to_string()),
+        Family::LZ4 => Ok(Box::new(FrameDecoder::new(bytes))),
+        _ => Err(format!("{:?} isn't compressed", family)),
+    }
+}
+
+pub fn inflate(family: Family, bytes: &[u8], limit: u64) -> Result<Vec<u8>, String> {
+    let mut inflated = vec![];
+
+    // one byte past the limit is enough to know it's too big
+    decoder(family, bytes)?.take(limit + 1)
+                           .read_to_end(&mut inflated)
+                           .map_err(|e| e.to_string())?;
+
+    if inflated.len() as u64 > limit {
+        return Err(format!("inflates to more than the {} byte limit", limit));
+    }
+
+    Ok(inflated)
+}
+
+// how many times bigger it got, e.g. 4.0 for a quarter of the size
+pub fn ratio(compressed: usize, inflated: usize) -> f64 {
+    match compressed {
+        0 => 0.0,
+        _ => inflated as f64 / compressed as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "hello hello hello hello hello hello", compressed by the usual command line tools
+    const HELLO: &str = "hello hello hello hello hello hello";
+    const GZIP: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00W7b\x85#\x00\x00\x00";
+    const ZLIB: &[u8] = b"x\x9c\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00\xebU\x0d\x19";
+    const DEFLATE: &[u8] = b"\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00";
+
+    #[test]
+    fn inflates() {
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::GZIP, GZIP, LIMIT));
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::ZLIB, ZLIB, LIMIT));
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::DEFLATE, DEFLATE, LIMIT));
+    }
+
+    #[test]
+    fn round_trips() {
+        let bzip2 = {
+            let mut out = vec![];
+            bzip2::read::BzEncoder::new(HELLO.as_bytes(), bzip2::Compression::best()).read_to_end(&mut out).expect("bad test config");
+            out
+        };
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::BZIP2, &bzip2, LIMIT));
+
+        let zstd = zstd::encode_all(HELLO.as_bytes(), 0).expect("bad test config");
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::ZSTD, &zstd, LIMIT));
+
+        let lz4 = {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(vec![]);
+            std::io::Write::write_all(&mut encoder, HELLO.as_bytes()).expect("bad test config");
+            encoder.finish().expect("bad test config")
+        };
+        assert_eq!(Ok(HELLO.as_bytes().to_vec()), inflate(Family::LZ4, &lz4, LIMIT));
+    }
+
+    #[test]
+    fn bombs_are_defused() {
+        assert_eq!(Err("inflates to more than the 10 byte limit".to_owned()), inflate(Family::GZIP, GZIP, 10));
+    }
+
+    #[test]
+    fn corrupt_or_not_compressed() {
+        assert!(inflate(Family::GZIP, &GZIP[..20], LIMIT).is_err());
+        assert!(inflate(Family::ZLIB, b"hello", LIMIT).is_err());
+        assert!(inflate(Family::JSON, b"{}", LIMIT).is_err());
+    }
+
+    #[test]
+    fn ratios() {
+        assert_eq!(4.0, ratio(10, 40));
+        assert_eq!(0.0, ratio(0, 40));
+        assert!(Family::COMPRESSED.iter().all(|f| algorithm(*f).is_some()));
+    }
+}
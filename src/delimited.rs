@@ -0,0 +1,318 @@
+// delimiter separated values, where the delimiter, the header and the column types all have to be guessed
+
+use csv::ReaderBuilder;
+use report_card::{self, ParseError};
+
+pub const DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnType {
+    Empty,
+    Integer,
+    Decimal,
+    Boolean,
+    Date,
+    Text,
+}
+
+impl ColumnType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnType::Empty => "empty",
+            ColumnType::Integer => "integer",
+            ColumnType::Decimal => "decimal",
+            ColumnType::Boolean => "boolean",
+            ColumnType::Date => "date",
+            ColumnType::Text => "text",
+        }
+    }
+
+    fn of(cell: &str) -> ColumnType {
+        let cell = cell.trim();
+
+        if cell.is_empty() {
+            ColumnType::Empty
+        } else if cell.parse::<i64>().is_ok() {
+            ColumnType::Integer
+        } else if cell.parse::<f64>().is_ok() && cell.chars().any(|c| c.is_ascii_digit()) {
+            ColumnType::Decimal
+        } else if ["true", "false"].contains(&cell.to_lowercase().as_str()) {
+            ColumnType::Boolean
+        } else if is_date(cell) {
+            ColumnType::Date
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    // the narrowest type that fits both, e.g. integers and decimals are decimals
+    fn widen(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Empty, b) => b,
+            (a, ColumnType::Empty) => a,
+            (ColumnType::Integer, ColumnType::Decimal) | (ColumnType::Decimal, ColumnType::Integer) => ColumnType::Decimal,
+            _ => ColumnType::Text,
+        }
+    }
+
+    pub fn is_numeric(self) -> bool {
+        self == ColumnType::Integer || self == ColumnType::Decimal
+    }
+}
+
+// YYYY-MM-DD, optionally followed by a time
+fn is_date(cell: &str) -> bool {
+    let date = cell.get(..10).unwrap_or("");
+    let digits = |range: std::ops::Range<usize>| date.get(range).map(|d| d.chars().all(|c| c.is_ascii_digit())).unwrap_or(false);
+
+    date.len() == 10 && digits(0..4) && date.get(4..5) == Some("-") && digits(5..7) && date.get(7..8) == Some("-")
+        && digits(8..10) && (cell.len() == 10 || cell[10..].starts_with(['T', ' ']))
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Row {
+    // the line the row starts on, as quoted fields can span lines
+    pub line: u32,
+    pub fields: Vec<String>,
+}
+
+// RFC 4180 style, where "" inside a quoted field is a quote and blank lines are skipped
+pub fn rows(input: &str, delimiter: char) -> Vec<Row> {
+    let mut reader = ReaderBuilder::new().has_headers(false)
+                                         .flexible(true)
+                                         .delimiter(delimiter as u8)
+                                         .from_reader(input.as_bytes());
+
+    // reading from a str, there's no IO or UTF-8 to fail
+    reader.records()
+          .filter_map(|record| record.ok())
+          .map(|record| {
+              // csv's position is from before the blank lines it skipped, so the row starts after them
+              let from = record.position().map(|p| p.byte() as usize).unwrap_or(0);
+              let start = input[from..].find(|c| c != '\r' && c != '\n').map(|i| from + i).unwrap_or(from);
+              Row { line: report_card::position_at(input, start).0, fields: record.iter().map(String::from).collect() }
+          })
+          .collect()
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Table {
+    pub delimiter: char,
+    pub header: Option<Vec<String>>,
+    pub rows: Vec<Row>,
+    // how many fields most rows have
+    pub columns: usize,
+}
+
+impl Table {
+    pub fn ragged(&self) -> Vec<&Row> {
+        self.rows.iter().filter(|r| r.fields.len() != self.columns).collect()
+    }
+
+    pub fn column_types(&self) -> Vec<ColumnType> {
+        (0..self.columns).map(|i| {
+                             self.rows.iter()
+                                      .filter_map(|r| r.fields.get(i))
+                                      .map(|cell| ColumnType::of(cell))
+                                      .fold(ColumnType::Empty, ColumnType::widen)
+                         })
+                         .collect()
+    }
+
+    // a row for each line that doesn't have the usual number of fields
+    pub fn errors(&self) -> Vec<ParseError> {
+        self.ragged()
+            .iter()
+            .map(|r| {
+                let fields = if r.fields.len() == 1 { "field" } else { "fields" };
+                ParseError::new(&format!("row has {} {} where the others have {}", r.fields.len(), fields, self.columns), r.line, 1)
+            })
+            .collect()
+    }
+}
+
+fn most_common(counts: &[usize]) -> usize {
+    let mut best = (0, 0);
+
+    for &count in counts {
+        let seen = counts.iter().filter(|c| **c == count).count();
+        if seen > best.1 || (seen == best.1 && count > best.0) {
+            best = (count, seen);
+        }
+    }
+
+    best.0
+}
+
+// a first row of labels over at least one column of something else, or labels that never appear again
+fn has_header(rows: &[Row], columns: usize) -> bool {
+    let first = &rows[0].fields;
+    let data = &rows[1..];
+
+    if first.iter().any(|f| ColumnType::of(f) != ColumnType::Text) {
+        return false;
+    }
+
+    let typed = (0..columns).any(|i| {
+        data.iter()
+            .filter_map(|r| r.fields.get(i))
+            .map(|cell| ColumnType::of(cell))
+            .fold(ColumnType::Empty, ColumnType::widen) != ColumnType::Text
+    });
+
+    let unique = first.iter().enumerate().all(|(i, label)| {
+        data.iter().all(|r| r.fields.get(i) != Some(label))
+    });
+
+    typed || unique
+}
+
+// the delimiter that splits the most rows into the same number of columns
+pub fn sniff(input: &str) -> Option<Table> {
+    let mut best: Option<(usize, Table)> = None;
+
+    for delimiter in DELIMITERS.iter() {
+        let rows = rows(input, *delimiter);
+        if rows.len() < 2 {
+            continue;
+        }
+
+        let counts: Vec<usize> = rows.iter().map(|r| r.fields.len()).collect();
+        let columns = most_common(&counts);
+        let consistent = counts.iter().filter(|c| **c == columns).count();
+
+        if columns < 2 || best.as_ref().map(|(b, _)| consistent <= *b).unwrap_or(false) {
+            continue;
+        }
+
+        let (header, rows) = if has_header(&rows, columns) {
+            (Some(rows[0].fields.clone()), rows[1..].to_vec())
+        } else {
+            (None, rows)
+        };
+
+        best = Some((consistent, Table { delimiter: *delimiter, header, rows, columns }));
+    }
+
+    best.map(|(_, table)| table)
+}
+
+// the header, or numbers for a table without one
+pub fn labels(table: &Table) -> Vec<String> {
+    match table.header {
+        Some(ref header) => header.clone(),
+        None => {
+            let width = table.rows.iter().map(|r| r.fields.len()).max().unwrap_or(0).max(table.columns);
+            (1..=width).map(|i| format!("column {}", i)).collect()
+        }
+    }
+}
+
+fn pad(cell: &str, width: usize, right: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(cell.chars().count()));
+
+    if right {
+        format!("{}{}", padding, cell)
+    } else {
+        format!("{}{}", cell, padding)
+    }
+}
+
+// lined up in columns, numbers on the right, with the header ruled off
+pub fn render(table: &Table) -> String {
+    let types = table.column_types();
+    let width = table.rows.iter().map(|r| r.fields.len()).max().unwrap_or(0).max(table.columns);
+    let labels = labels(table);
+
+    // multi-line fields would break the layout
+    let cell = |row: &[String], i: usize| row.get(i).map(|c| c.replace('\n', " ").trim().to_owned()).unwrap_or_default();
+
+    let widths: Vec<usize> = (0..width)
+        .map(|i| {
+            table.rows.iter()
+                      .map(|r| cell(&r.fields, i).chars().count())
+                      .chain(Some(cell(&labels, i).chars().count()))
+                      .max()
+                      .unwrap_or(0)
+        })
+        .collect();
+
+    let line = |row: &[String]| -> String {
+        (0..width).map(|i| pad(&cell(row, i), widths[i], types.get(i).map(|t| t.is_numeric()).unwrap_or(false)))
+                  .collect::<Vec<String>>()
+                  .join("  ")
+                  .trim_end()
+                  .to_owned()
+    };
+
+    let mut lines = vec![line(&labels), widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<String>>().join("  ")];
+    lines.extend(table.rows.iter().map(|r| line(&r.fields)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(row: &Row) -> Vec<&str> {
+        row.fields.iter().map(|f| f.as_str()).collect()
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let rows = rows("a,\"b, \"\"c\"\"\",d\n\"multi\nline\",2,3\n", ',');
+        assert_eq!(vec!["a", "b, \"c\"", "d"], fields(&rows[0]));
+        assert_eq!(vec!["multi\nline", "2", "3"], fields(&rows[1]));
+        assert_eq!(2, rows.len());
+    }
+
+    #[test]
+    fn rows_start_on_their_own_line() {
+        let rows = rows("\"multi\nline\",1\n\n2,3\n", ',');
+        assert_eq!(vec![1, 4], rows.iter().map(|r| r.line).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn sniffs_the_delimiter_and_header() {
+        let table = sniff("name\tage\nalice\t30\nbob\t4").expect("bad test config");
+        assert_eq!('\t', table.delimiter);
+        assert_eq!(Some(vec!["name".to_owned(), "age".to_owned()]), table.header);
+        assert_eq!(2, table.columns);
+        assert_eq!(vec![ColumnType::Text, ColumnType::Integer], table.column_types());
+
+        let table = sniff("1;2;3\n4;5;6").expect("bad test config");
+        assert_eq!(';', table.delimiter);
+        assert_eq!(None, table.header);
+
+        assert_eq!(None, sniff("just one line, with a comma"));
+        assert_eq!(None, sniff("no delimiters\nat all"));
+    }
+
+    #[test]
+    fn column_types() {
+        assert_eq!(ColumnType::Decimal, ColumnType::Integer.widen(ColumnType::Decimal));
+        assert_eq!(ColumnType::Date, ColumnType::of("2024-02-29T10:00:00Z"));
+        assert_eq!(ColumnType::Boolean, ColumnType::of("TRUE"));
+        assert_eq!(ColumnType::Text, ColumnType::of("inf"));
+        assert_eq!(ColumnType::Text, ColumnType::of("2024é-01-01"));
+        assert_eq!(ColumnType::Text, ColumnType::of("2024-01-01é"));
+        assert_eq!(ColumnType::Text, ColumnType::Date.widen(ColumnType::Integer));
+    }
+
+    #[test]
+    fn ragged_rows_are_errors() {
+        let table = sniff("a,b,c\n1,2,3\n4,5\n6,7,8").expect("bad test config");
+        assert_eq!(vec![ParseError::new("row has 2 fields where the others have 3", 3, 1)], table.errors());
+    }
+
+    #[test]
+    fn renders_aligned() {
+        let table = sniff("name,age,joined\nalice,30,2020-01-01\nbob,4,\n").expect("bad test config");
+        let expected = "name   age  joined\n\
+                        -----  ---  ----------\n\
+                        alice   30  2020-01-01\n\
+                        bob      4";
+        assert_eq!(expected, render(&table));
+    }
+}
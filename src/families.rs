@@ -1,6 +1,65 @@
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Family {
     JSON,
+    SELF_DESCRIBING_JSON,
     BASE64,
+    JWT,
+    BINARY,
     UNSTRUCTURED,
-}
\ No newline at end of file
+    PNG,
+    JPEG,
+    GIF,
+    PDF,
+    ZIP,
+    GZIP,
+    ZSTD,
+    BZIP2,
+    XZ,
+    ELF,
+    WASM,
+    SQLITE,
+    JAVA_SERIALIZED,
+    PARQUET,
+    AVRO,
+    LZ4,
+    ZLIB,
+    DEFLATE,
+    BASE32,
+    BASE58,
+    ASCII85,
+    Z85,
+    HEX,
+    XML,
+    YAML,
+    TOML,
+    CSV,
+    URL,
+}
+
+impl Family {
+    // binary formats recognised by their magic numbers
+    pub const FILE_TYPES: [Family; 16] = [Family::PNG, Family::JPEG, Family::GIF, Family::PDF, Family::ZIP,
+                                          Family::GZIP, Family::ZSTD, Family::BZIP2, Family::XZ, Family::ELF,
+                                          Family::WASM, Family::SQLITE, Family::JAVA_SERIALIZED, Family::PARQUET,
+                                          Family::AVRO, Family::LZ4];
+
+    // streams that can be inflated, only some of which have magic numbers
+    pub const COMPRESSED: [Family; 6] = [Family::GZIP, Family::ZLIB, Family::DEFLATE, Family::BZIP2, Family::ZSTD,
+                                         Family::LZ4];
+
+    // text that stands in for arbitrary bytes
+    pub const ENCODINGS: [Family; 6] = [Family::BASE64, Family::BASE32, Family::BASE58, Family::ASCII85, Family::Z85,
+                                        Family::HEX];
+
+    pub fn is_file_type(self) -> bool {
+        Family::FILE_TYPES.contains(&self)
+    }
+
+    pub fn is_compressed(self) -> bool {
+        Family::COMPRESSED.contains(&self)
+    }
+
+    pub fn is_encoding(self) -> bool {
+        Family::ENCODINGS.contains(&self)
+    }
+}
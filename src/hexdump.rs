@@ -0,0 +1,87 @@
+// offset, hex and printable ASCII side by side, like hexdump -C, for bytes that aren't text
+
+use colour::{Palette, Role};
+use magic;
+
+const BYTES_PER_LINE: usize = 16;
+
+fn printable(byte: u8) -> char {
+    if byte == b' ' || byte.is_ascii_graphic() {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+pub fn dump(bytes: &[u8], palette: &Palette) -> String {
+    // the signature that gives the file type away stands out
+    let magic = magic::locate(bytes).map(|(offset, len, _)| offset..offset + len);
+    let paint = |i: usize, text: String| match magic {
+        Some(ref range) if range.contains(&i) => palette.paint(Role::Magic, &text),
+        _ => text,
+    };
+
+    let mut lines = vec![];
+
+    for (n, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        let start = n * BYTES_PER_LINE;
+
+        let hex: Vec<String> = (0..BYTES_PER_LINE)
+            .map(|i| match chunk.get(i) {
+                Some(byte) => paint(start + i, format!("{:02x}", byte)),
+                None => "  ".to_owned(),
+            })
+            .collect();
+
+        let ascii: String = chunk.iter()
+                                 .enumerate()
+                                 .map(|(i, byte)| paint(start + i, printable(*byte).to_string()))
+                                 .collect();
+
+        let line = format!("{}  {}  {}  |{}|",
+                           palette.paint(Role::Gutter, &format!("{:08x}", start)),
+                           hex[..8].join(" "),
+                           hex[8..].join(" "),
+                           ascii);
+        lines.push(line);
+    }
+
+    lines.push(palette.paint(Role::Gutter, &format!("{:08x}", bytes.len())));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use theme::{Depth, Theme};
+
+    #[test]
+    fn lines_and_gutter() {
+        let bytes: Vec<u8> = (0x3e..0x52).collect();
+
+        assert_eq!("00000000  3e 3f 40 41 42 43 44 45  46 47 48 49 4a 4b 4c 4d  |>?@ABCDEFGHIJKLM|\n\
+                    00000010  4e 4f 50 51                                       |NOPQ|\n\
+                    00000014",
+                   dump(&bytes, &Palette::plain()));
+    }
+
+    #[test]
+    fn unprintable_bytes_are_dots() {
+        assert_eq!("00000000  00 0a 20 7f ff                                    |.. ..|\n00000005",
+                   dump(&[0x00, 0x0a, 0x20, 0x7f, 0xff], &Palette::plain()));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!("00000000", dump(&[], &Palette::plain()));
+    }
+
+    #[test]
+    fn magic_numbers_stand_out() {
+        let palette = Palette::new(Theme::default(), Depth::Sixteen);
+        let dumped = dump(b"\x1f\x8b\x08\x00", &palette);
+
+        assert!(dumped.contains(&format!("{} {} 08 00", palette.paint(Role::Magic, "1f"), palette.paint(Role::Magic, "8b"))));
+        assert!(dumped.contains(&format!("|{}{}..|", palette.paint(Role::Magic, "."), palette.paint(Role::Magic, "."))));
+    }
+}
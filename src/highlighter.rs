@@ -0,0 +1,276 @@
+// syntax highlighting for decoded output, picked by the family it was recognised as
+
+use families::Family;
+use colour::{Palette, Role};
+use serde_json::{self, Value};
+use yaml;
+
+// just past the closing quote of the JSON string that starts at start
+fn string_end(chars: &[char], start: usize) -> usize {
+    let mut i = start + 1;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    chars.len()
+}
+
+// a string is a key if the next thing after it is a colon
+fn is_key(chars: &[char], end: usize) -> bool {
+    chars[end..].iter().find(|c| !c.is_whitespace()) == Some(&':')
+}
+
+fn word_role(word: &str) -> Option<Role> {
+    match word {
+        "true" | "false" => Some(Role::Boolean),
+        "null" => Some(Role::Null),
+        _ if word.starts_with(|c: char| c == '-' || c.is_ascii_digit()) => Some(Role::Number),
+        _ => None,
+    }
+}
+
+// colour JSON text token by token, leaving the layout exactly as it was
+fn highlight_json(text: &str, palette: &Palette) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '"' {
+            let end = string_end(&chars, i);
+            let token: String = chars[i..end].iter().collect();
+            let role = if is_key(&chars, end) { Role::Key } else { Role::String };
+            out.push_str(&palette.paint(role, &token));
+            i = end;
+        } else if c == '-' || c.is_ascii_alphanumeric() {
+            let end = chars[i..].iter()
+                                .position(|c| !(c.is_ascii_alphanumeric() || "+-.".contains(*c)))
+                                .map(|p| i + p)
+                                .unwrap_or(chars.len());
+            let token: String = chars[i..end].iter().collect();
+
+            match word_role(&token) {
+                Some(role) => out.push_str(&palette.paint(role, &token)),
+                None => out.push_str(&token),
+            }
+            i = end;
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+// the name of an element in a tag, then its attribute values
+fn highlight_tag(tag: &str, palette: &Palette) -> String {
+    let name_start = tag.find(|c: char| !"<?/".contains(c)).unwrap_or(tag.len());
+    let name_end = tag[name_start..].find(|c: char| c.is_whitespace() || "/?>".contains(c))
+                                    .map(|e| name_start + e)
+                                    .unwrap_or(tag.len());
+
+    let mut out = format!("{}{}", &tag[..name_start], palette.paint(Role::Key, &tag[name_start..name_end]));
+    let mut quote = None;
+    let mut value = String::new();
+
+    for c in tag[name_end..].chars() {
+        match quote {
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                value.push(c);
+            }
+            None => out.push(c),
+            Some(q) => {
+                value.push(c);
+                if c == q {
+                    out.push_str(&palette.paint(Role::String, &value));
+                    value.clear();
+                    quote = None;
+                }
+            }
+        }
+    }
+
+    out.push_str(&value);
+    out
+}
+
+// colour XML tag by tag, passing comments, CDATA and DOCTYPEs through as they are
+fn highlight_xml(text: &str, palette: &Palette) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let end = rest.find(terminator).map(|e| e + terminator.len()).unwrap_or(rest.len());
+
+        if rest.starts_with("<!") {
+            out.push_str(&rest[..end]);
+        } else {
+            out.push_str(&highlight_tag(&rest[..end], palette));
+        }
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn highlight_scalar(value: &str, palette: &Palette) -> String {
+    if value.starts_with('"') || value.starts_with('\'') {
+        return palette.paint(Role::String, value);
+    }
+
+    match word_role(value) {
+        Some(role) if !value.contains(' ') => palette.paint(role, value),
+        _ => value.to_owned(),
+    }
+}
+
+// colour [table] headers, keys and simple values of TOML, line by line
+fn highlight_toml(text: &str, palette: &Palette) -> String {
+    text.lines()
+        .map(|line| {
+            if line.starts_with('[') {
+                return palette.paint(Role::Key, line);
+            }
+
+            match line.find(" = ") {
+                Some(equals) => format!("{} = {}", palette.paint(Role::Key, &line[..equals]), highlight_scalar(&line[equals + 3..], palette)),
+                None => line.to_owned(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// colour the keys and simple values of block style YAML, line by line
+fn highlight_yaml(text: &str, palette: &Palette) -> String {
+    text.lines()
+        .map(|line| {
+            let body = line.trim_start_matches([' ', '-']);
+            let prefix = &line[..line.len() - body.len()];
+
+            match yaml::split_key(body) {
+                Some((key, value)) => {
+                    let separator = if value.is_empty() { ":" } else { ": " };
+                    format!("{}{}{}{}", prefix, palette.paint(Role::Key, key), separator, highlight_scalar(value, palette))
+                }
+                None => format!("{}{}", prefix, highlight_scalar(body, palette)),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// text that turned out to be a JSON document once decoded, e.g. from base64
+fn looks_like_json(text: &str) -> bool {
+    matches!(serde_json::from_str::<Value>(text), Ok(Value::Object(_)) | Ok(Value::Array(_)))
+}
+
+pub fn highlight(family: Family, text: &str, palette: &Palette) -> String {
+    match family {
+        Family::JSON | Family::SELF_DESCRIBING_JSON => highlight_json(text, palette),
+        Family::XML => highlight_xml(text, palette),
+        Family::YAML => highlight_yaml(text, palette),
+        Family::TOML => highlight_toml(text, palette),
+        family if family.is_encoding() && looks_like_json(text) => highlight_json(text, palette),
+        _ => text.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_is_untouched() {
+        let json = "{\n  \"a\": [1, -2.5e3, true, null, \"x\\\"y\"]\n}";
+        assert_eq!(json, highlight(Family::JSON, json, &Palette::plain()));
+    }
+
+    #[test]
+    fn json_tokens() {
+        let palette = Palette::ansi();
+        let json = "{\"a\": [1, true, null, \"x\\\"y\"]}";
+
+        let expected = format!("{{{}: [{}, {}, {}, {}]}}",
+                               palette.paint(Role::Key, "\"a\""),
+                               palette.paint(Role::Number, "1"),
+                               palette.paint(Role::Boolean, "true"),
+                               palette.paint(Role::Null, "null"),
+                               palette.paint(Role::String, "\"x\\\"y\""));
+
+        assert_eq!(expected, highlight(Family::JSON, json, &palette));
+    }
+
+    #[test]
+    fn xml_tags() {
+        let palette = Palette::ansi();
+        let xml = "<a x=\"1\"><!-- <b> --><![CDATA[<c>]]>text</a>";
+
+        let expected = format!("<{} x={}><!-- <b> --><![CDATA[<c>]]>text</{}>",
+                               palette.paint(Role::Key, "a"),
+                               palette.paint(Role::String, "\"1\""),
+                               palette.paint(Role::Key, "a"));
+
+        assert_eq!(expected, highlight(Family::XML, xml, &palette));
+        assert_eq!(xml, highlight(Family::XML, xml, &Palette::plain()));
+    }
+
+    #[test]
+    fn yaml_keys_and_values() {
+        let palette = Palette::ansi();
+        let yaml = "a: 1\nb:\n  - 'x'\n  - c: true\n  - plain words";
+
+        let expected = format!("{}: {}\n{}:\n  - {}\n  - {}: {}\n  - plain words",
+                               palette.paint(Role::Key, "a"),
+                               palette.paint(Role::Number, "1"),
+                               palette.paint(Role::Key, "b"),
+                               palette.paint(Role::String, "'x'"),
+                               palette.paint(Role::Key, "c"),
+                               palette.paint(Role::Boolean, "true"));
+
+        assert_eq!(expected, highlight(Family::YAML, yaml, &palette));
+        assert_eq!(yaml, highlight(Family::YAML, yaml, &Palette::plain()));
+    }
+
+    #[test]
+    fn toml_headers_keys_and_values() {
+        let palette = Palette::ansi();
+        let toml = "a = 1\n\n[b]\nc = \"x\"";
+
+        let expected = format!("{} = {}\n\n{}\n{} = {}",
+                               palette.paint(Role::Key, "a"),
+                               palette.paint(Role::Number, "1"),
+                               palette.paint(Role::Key, "[b]"),
+                               palette.paint(Role::Key, "c"),
+                               palette.paint(Role::String, "\"x\""));
+
+        assert_eq!(expected, highlight(Family::TOML, toml, &palette));
+    }
+
+    #[test]
+    fn decoded_base64_follows_its_content() {
+        let palette = Palette::ansi();
+        assert_eq!(format!("[{}]", palette.paint(Role::Number, "1")), highlight(Family::BASE64, "[1]", &palette));
+        assert_eq!("hello 1", highlight(Family::BASE64, "hello 1", &palette));
+    }
+}
@@ -0,0 +1,251 @@
+// Iglu schema URIs, e.g. iglu:com.snowplowanalytics.snowplow/link_click/jsonschema/1-0-1,
+// and the static registries they resolve against
+
+use serde_json::{self, Value};
+use jsonschema::{self, Draft};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+const IGLU_PROTOCOL: &str = "iglu:";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SchemaVer {
+    pub model: u32,
+    pub revision: u32,
+    pub addition: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SchemaKey {
+    pub vendor: String,
+    pub name: String,
+    pub format: String,
+    pub version: SchemaVer,
+}
+
+impl fmt::Display for SchemaVer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.model, self.revision, self.addition)
+    }
+}
+
+impl fmt::Display for SchemaKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}/{}/{}/{}", IGLU_PROTOCOL, self.vendor, self.name, self.format, self.version)
+    }
+}
+
+fn parse_schema_ver(version: &str) -> Result<SchemaVer, String> {
+    let parts: Vec<&str> = version.split('-').collect();
+
+    if parts.len() != 3 {
+        return Err(format!("version '{}' should be MODEL-REVISION-ADDITION", version));
+    }
+
+    let mut numbers = vec![];
+    for part in parts {
+        let is_number = !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+        if !is_number || (part.len() > 1 && part.starts_with('0')) {
+            return Err(format!("version '{}' should only contain whole numbers without leading zeros", version));
+        }
+        numbers.push(part.parse::<u32>().map_err(|_| format!("version '{}' is too large", version))?);
+    }
+
+    if numbers[0] == 0 {
+        return Err(format!("version '{}' should have a MODEL of at least 1", version));
+    }
+
+    Ok(SchemaVer {
+        model: numbers[0],
+        revision: numbers[1],
+        addition: numbers[2],
+    })
+}
+
+fn valid_part(part: &str) -> bool {
+    !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+}
+
+pub fn parse(uri: &str) -> Result<SchemaKey, String> {
+    if !uri.starts_with(IGLU_PROTOCOL) {
+        return Err(format!("schema '{}' should start with '{}'", uri, IGLU_PROTOCOL));
+    }
+
+    let parts: Vec<&str> = uri[IGLU_PROTOCOL.len()..].split('/').collect();
+
+    if parts.len() != 4 {
+        return Err(format!("schema '{}' should look like iglu:vendor/name/format/version", uri));
+    }
+
+    if let Some(bad) = parts[..3].iter().find(|p| !valid_part(p)) {
+        return Err(format!("schema '{}' has an invalid vendor, name or format '{}'", uri, bad));
+    }
+
+    Ok(SchemaKey {
+        vendor: parts[0].to_owned(),
+        name: parts[1].to_owned(),
+        format: parts[2].to_owned(),
+        version: parse_schema_ver(parts[3])?,
+    })
+}
+
+// a self describing JSON object has exactly a "schema" string and "data"
+pub fn envelope(value: &Value) -> Option<(&str, &Value)> {
+    let map = value.as_object()?;
+
+    if map.len() != 2 {
+        return None;
+    }
+
+    let schema = map.get("schema").and_then(|s| s.as_str())?;
+    let data = map.get("data")?;
+
+    Some((schema, data))
+}
+
+// a static registry on disk, laid out as schemas/vendor/name/format/version
+#[derive(Debug, PartialEq, Clone)]
+pub struct Registry {
+    root: PathBuf,
+}
+
+// a single place where data doesn't match its schema
+#[derive(Debug, PartialEq, Clone)]
+pub struct Violation {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Registry {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Registry { root: root.into() }
+    }
+
+    pub fn path_for(&self, key: &SchemaKey) -> PathBuf {
+        self.root
+            .join("schemas")
+            .join(&key.vendor)
+            .join(&key.name)
+            .join(&key.format)
+            .join(key.version.to_string())
+    }
+
+    pub fn resolve(&self, key: &SchemaKey) -> Result<Value, String> {
+        let path = self.path_for(key);
+        let mut contents = String::new();
+
+        File::open(&path)
+            .and_then(|mut f| f.read_to_string(&mut contents))
+            .map_err(|_| format!("couldn't find {} in the iglu repo at '{}'", key, path.display()))?;
+
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("schema for {} at '{}' isn't valid JSON - {}", key, path.display(), e))
+    }
+
+    // Iglu schemas are JSON Schema draft 4, self described with a $schema the validator doesn't know
+    pub fn validate(&self, key: &SchemaKey, data: &Value) -> Result<Vec<Violation>, String> {
+        let mut schema = self.resolve(key)?;
+
+        if let Some(map) = schema.as_object_mut() {
+            map.remove("$schema");
+        }
+
+        let validator = jsonschema::options()
+            .with_draft(Draft::Draft4)
+            .build(&schema)
+            .map_err(|e| format!("schema for {} isn't a usable JSON schema - {}", key, e))?;
+
+        Ok(validator.iter_errors(data)
+            .map(|e| Violation {
+                pointer: e.instance_path.as_str().to_owned(),
+                message: e.to_string(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_good() {
+        let key = parse("iglu:com.acme/my_event/jsonschema/1-0-2").expect("should parse");
+        assert_eq!(key,
+                   SchemaKey {
+                       vendor: "com.acme".into(),
+                       name: "my_event".into(),
+                       format: "jsonschema".into(),
+                       version: SchemaVer { model: 1, revision: 0, addition: 2 },
+                   });
+        assert_eq!("iglu:com.acme/my_event/jsonschema/1-0-2", key.to_string());
+    }
+
+    #[test]
+    fn parse_bad_protocol() {
+        assert_eq!(Err("schema 'http://acme.com/schema' should start with 'iglu:'".to_owned()),
+                   parse("http://acme.com/schema"));
+    }
+
+    #[test]
+    fn parse_bad_shape() {
+        assert_eq!(Err("schema 'iglu:com.acme/my_event/1-0-0' should look like iglu:vendor/name/format/version".to_owned()),
+                   parse("iglu:com.acme/my_event/1-0-0"));
+    }
+
+    #[test]
+    fn parse_bad_schema_ver() {
+        assert!(parse("iglu:com.acme/my_event/jsonschema/1-0").is_err());
+        assert!(parse("iglu:com.acme/my_event/jsonschema/0-1-0").is_err());
+        assert!(parse("iglu:com.acme/my_event/jsonschema/1-01-0").is_err());
+        assert!(parse("iglu:com.acme/my_event/jsonschema/1-a-0").is_err());
+    }
+
+    fn registry() -> Registry {
+        Registry::new("tests/resources/iglu")
+    }
+
+    #[test]
+    fn registry_paths() {
+        let key = parse("iglu:com.acme/my_event/jsonschema/1-0-2").expect("bad test config");
+        assert_eq!(PathBuf::from("tests/resources/iglu/schemas/com.acme/my_event/jsonschema/1-0-2"),
+                   registry().path_for(&key));
+    }
+
+    #[test]
+    fn registry_missing_schema() {
+        let key = parse("iglu:com.acme/missing/jsonschema/1-0-0").expect("bad test config");
+        assert_eq!(Err("couldn't find iglu:com.acme/missing/jsonschema/1-0-0 in the iglu repo at \
+                        'tests/resources/iglu/schemas/com.acme/missing/jsonschema/1-0-0'".to_owned()),
+                   registry().validate(&key, &json!({})));
+    }
+
+    #[test]
+    fn registry_valid_data() {
+        let key = parse("iglu:com.acme/link_click/jsonschema/1-0-0").expect("bad test config");
+        assert_eq!(Ok(vec![]), registry().validate(&key, &json!({"targetUrl": "http://acme.com", "elementId": "a"})));
+    }
+
+    #[test]
+    fn registry_invalid_data() {
+        let key = parse("iglu:com.acme/link_click/jsonschema/1-0-0").expect("bad test config");
+        let violations = registry().validate(&key, &json!({"elementId": 5})).expect("bad test config");
+
+        let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(vec!["/elementId", ""], pointers);
+        assert!(violations[1].message.contains("targetUrl"));
+    }
+
+    #[test]
+    fn envelopes() {
+        let good = json!({"schema": "iglu:com.acme/e/jsonschema/1-0-0", "data": {"a": 1}});
+        assert_eq!(Some(("iglu:com.acme/e/jsonschema/1-0-0", &json!({"a": 1}))), envelope(&good));
+
+        assert_eq!(None, envelope(&json!({"schema": "x"})));
+        assert_eq!(None, envelope(&json!({"schema": 1, "data": 2})));
+        assert_eq!(None, envelope(&json!({"schema": "x", "data": 2, "extra": 3})));
+        assert_eq!(None, envelope(&json!([1, 2])));
+    }
+}
@@ -0,0 +1,88 @@
+// whatever is being scrutinized, as raw bytes that may or may not also be text
+
+use std::borrow::Cow;
+use std::str;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    bytes: Vec<u8>,
+}
+
+impl Input {
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    // only if every byte is valid UTF-8
+    pub fn text(&self) -> Option<&str> {
+        str::from_utf8(&self.bytes).ok()
+    }
+
+    // the text, with anything that isn't UTF-8 replaced
+    pub fn lossy(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.bytes)
+    }
+
+    // offset of the first byte that stops this being UTF-8 text
+    pub fn utf8_error_offset(&self) -> Option<usize> {
+        str::from_utf8(&self.bytes).err().map(|e| e.valid_up_to())
+    }
+
+    // surrounding whitespace is noise in text, but could be meaningful bytes in binary
+    pub fn trimmed(&self) -> Input {
+        match self.text() {
+            Some(text) => Input::from(text.trim()),
+            None => self.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for Input {
+    fn from(bytes: Vec<u8>) -> Self {
+        Input { bytes }
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Input { bytes: text.into_bytes() }
+    }
+}
+
+impl<'a> From<&'a str> for Input {
+    fn from(text: &'a str) -> Self {
+        Input { bytes: text.as_bytes().to_vec() }
+    }
+}
+
+impl<'a> From<&'a [u8]> for Input {
+    fn from(bytes: &'a [u8]) -> Self {
+        Input { bytes: bytes.to_vec() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text() {
+        let input = Input::from("  hello\n");
+        assert_eq!(Some("  hello\n"), input.text());
+        assert_eq!(None, input.utf8_error_offset());
+        assert_eq!(Input::from("hello"), input.trimmed());
+    }
+
+    #[test]
+    fn binary() {
+        let input = Input::from(vec![b' ', b'h', 0xff, 0xfe]);
+        assert_eq!(None, input.text());
+        assert_eq!(Some(2), input.utf8_error_offset());
+        assert_eq!(" h\u{fffd}\u{fffd}", input.lossy());
+        assert_eq!(input, input.trimmed());
+    }
+}
@@ -0,0 +1,245 @@
+// the analysis as a stable JSON document, for scripts and other tools
+//
+// {
+//   "schema_version": "1-0-0",
+//   "result": LAYER
+// }
+//
+// LAYER is
+//
+// {
+//   "path": null for the input or a decoded payload, otherwise a JSON pointer into the parent,
+//   "input": the text that was checked, with anything that isn't UTF-8 replaced,
+//   "family": e.g. "JSON", "BASE64", "PNG", "UNSTRUCTURED",
+//   "type_friendly_name": e.g. "Base64",
+//   "sureness": 0.0 to 1.0,
+//   "known_errors": [{"message": "...", "line": 1, "col": 2}] (line and col are 0 when unknown),
+//   "notes": ["..."],
+//   "decoded": the decoded value - JSON as JSON, text as a string, a JWT as header/claims/signature,
+//              XML re-indented or YAML and TOML normalised as a string, CSV as an array of rows
+//              (objects keyed by the header when there is one), a URL as its percent-decoded parts,
+//              or null if it couldn't be decoded or is binary,
+//   "layers": [LAYER] (payloads found inside this one),
+//   "report_cards": [{"strategy_id", "type_friendly_name", "family", "sureness",
+//                     "factors": [{"reason", "weight"}], "known_errors"}] (every strategy, best first)
+// }
+//
+// fields are only ever added within a schema version
+
+use ReportCard;
+use report_card::{Factor, ParseError};
+use layer::Layer;
+use families::Family;
+use serde_json::{self, Value};
+use decoder;
+use xml;
+use yaml;
+use toml::{self, Table};
+use delimited;
+use input::Input;
+
+pub const SCHEMA_VERSION: &str = "1-0-0";
+
+// f32 scores widen to f64 with noise on the end, e.g. 0.30000001192092896
+fn rounded(n: f32) -> Value {
+    json!((f64::from(n) * 100.0).round() / 100.0)
+}
+
+fn errors_to_json(errors: &[ParseError]) -> Value {
+    Value::Array(errors.iter()
+                       .map(|e| json!({"message": e.message, "line": e.line, "col": e.col}))
+                       .collect())
+}
+
+fn factors_to_json(factors: &[Factor]) -> Value {
+    Value::Array(factors.iter()
+                        .map(|f| json!({"reason": f.reason, "weight": rounded(f.weight)}))
+                        .collect())
+}
+
+fn card_to_json(card: &ReportCard) -> Value {
+    json!({
+        "strategy_id": card.strategy_id,
+        "type_friendly_name": card.type_friendly_name,
+        "family": format!("{:?}", card.family),
+        "sureness": rounded(card.sureness),
+        "factors": factors_to_json(&card.factors),
+        "known_errors": errors_to_json(&card.known_errors),
+    })
+}
+
+fn decoded_value(bytes: &Input, card: &ReportCard) -> Value {
+    let input: &str = &bytes.lossy();
+
+    match card.family {
+        Family::JSON | Family::SELF_DESCRIBING_JSON => serde_json::from_str(input).unwrap_or(Value::Null),
+        family if family.is_encoding() => {
+            decoder::decode(family, input)
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .map(Value::String)
+                .unwrap_or(Value::Null)
+        }
+        Family::JWT => {
+            decoder::decode_jwt(input)
+                .map(|jwt| json!({"header": jwt.header, "claims": jwt.claims, "signature": jwt.signature}))
+                .unwrap_or(Value::Null)
+        }
+        Family::XML => {
+            xml::parse(input)
+                .map(|nodes| Value::String(xml::pretty(&nodes)))
+                .unwrap_or(Value::Null)
+        }
+        Family::YAML => {
+            yaml::parse(input)
+                .map(|documents| Value::String(yaml::pretty(&documents)))
+                .unwrap_or(Value::Null)
+        }
+        Family::TOML => {
+            input.parse::<Table>()
+                 .ok()
+                 .and_then(|table| toml::to_string_pretty(&table).ok())
+                 .map(Value::String)
+                 .unwrap_or(Value::Null)
+        }
+        Family::CSV => {
+            delimited::sniff(input)
+                .map(|table| {
+                    let rows = table.rows.iter().map(|row| match table.header {
+                        Some(ref header) => Value::Object(header.iter().cloned().zip(row.fields.iter().cloned().map(Value::String)).collect()),
+                        None => json!(row.fields),
+                    });
+                    Value::Array(rows.collect())
+                })
+                .unwrap_or(Value::Null)
+        }
+        Family::URL => {
+            decoder::decode_url(input)
+                .map(|address| json!({
+                    "scheme": address.scheme,
+                    "host": address.host,
+                    "port": address.port,
+                    "path": address.path,
+                    "query": address.query,
+                    "fragment": address.fragment,
+                }))
+                .unwrap_or(Value::Null)
+        }
+        Family::UNSTRUCTURED => Value::String(input.to_owned()),
+        family if family.is_compressed() => {
+            card.inflated.clone()
+                .and_then(|inflated| String::from_utf8(inflated).ok())
+                .map(Value::String)
+                .unwrap_or(Value::Null)
+        }
+        _ => Value::Null,
+    }
+}
+
+fn layer_to_json(layer: &Layer) -> Value {
+    // the best of a bad lot is still nothing in particular, as in the text presenter
+    let nonsense = ReportCard::new(0.0, "nonsense".into(), Family::UNSTRUCTURED);
+    let top = match layer.top() {
+        top if top.sureness <= 0.0 => &nonsense,
+        top => top,
+    };
+
+    json!({
+        "path": layer.path,
+        "input": layer.input.lossy(),
+        "family": format!("{:?}", top.family),
+        "type_friendly_name": top.type_friendly_name,
+        "sureness": rounded(top.sureness),
+        "known_errors": errors_to_json(&top.known_errors),
+        "notes": top.notes,
+        "decoded": decoded_value(&layer.input, top),
+        "layers": layer.children.iter().map(layer_to_json).collect::<Vec<Value>>(),
+        "report_cards": layer.report_cards.iter().map(card_to_json).collect::<Vec<Value>>(),
+    })
+}
+
+pub fn present(layer: &Layer) -> String {
+    let document = json!({
+        "schema_version": SCHEMA_VERSION,
+        "result": layer_to_json(layer),
+    });
+
+    serde_json::to_string_pretty(&document).unwrap_or_else(|_| document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use families;
+
+    fn card(sureness: f32, name: &str, family: families::Family) -> ReportCard {
+        ReportCard::new(sureness, name.into(), family)
+    }
+
+    #[test]
+    fn nested_layers() {
+        let mut outer = Layer::new(None, "eyJhIjoiaGkifQ==".into(), vec![card(1.0, "Base64", families::Family::BASE64),
+                                                                         card(0.3, "JSON", families::Family::JSON)]);
+        let mut inner = Layer::new(None, "{\"a\":\"hi\"}".into(), vec![card(1.0, "JSON", families::Family::JSON)]);
+        inner.report_cards[0].known_errors.push(ParseError::new("oops", 1, 2));
+        outer.children.push(inner);
+
+        let document: Value = serde_json::from_str(&present(&outer)).expect("should be JSON");
+
+        assert_eq!(document["schema_version"], json!(SCHEMA_VERSION));
+
+        let result = &document["result"];
+        assert_eq!(result["path"], Value::Null);
+        assert_eq!(result["family"], json!("BASE64"));
+        assert_eq!(result["sureness"], json!(1.0));
+        assert_eq!(result["decoded"], json!("{\"a\":\"hi\"}"));
+        assert_eq!(result["report_cards"][1]["sureness"], json!(0.3));
+
+        let inner = &result["layers"][0];
+        assert_eq!(inner["family"], json!("JSON"));
+        assert_eq!(inner["decoded"], json!({"a": "hi"}));
+        assert_eq!(inner["known_errors"], json!([{"message": "oops", "line": 1, "col": 2}]));
+        assert_eq!(inner["layers"], json!([]));
+    }
+
+    #[test]
+    fn unsure_is_unstructured() {
+        let layer = Layer::new(None, "potato".into(), vec![card(0.0, "gzip compressed", families::Family::GZIP)]);
+        let result = layer_to_json(&layer);
+
+        assert_eq!(result["family"], json!("UNSTRUCTURED"));
+        assert_eq!(result["type_friendly_name"], json!("nonsense"));
+        assert_eq!(result["sureness"], json!(0.0));
+        assert_eq!(result["decoded"], json!("potato"));
+        assert_eq!(result["report_cards"][0]["family"], json!("GZIP"));
+    }
+
+    #[test]
+    fn undecodable_is_null() {
+        assert_eq!(Value::Null, decoded_value(&"!!!".into(), &card(1.0, "Base64", families::Family::BASE64)));
+        assert_eq!(Value::Null, decoded_value(&"{".into(), &card(1.0, "JSON", families::Family::JSON)));
+        assert_eq!(json!("words"), decoded_value(&"words".into(), &card(1.0, "nonsense", families::Family::UNSTRUCTURED)));
+    }
+
+    #[test]
+    fn compressed_text_is_inflated() {
+        let zlib = b"x\x9c\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00\xebU\x0d\x19";
+        let mut zlib_card = card(1.0, "zlib compressed", families::Family::ZLIB);
+        zlib_card.inflated = Some(b"hello hello hello hello hello hello".to_vec());
+        assert_eq!(json!("hello hello hello hello hello hello"), decoded_value(&zlib[..].into(), &zlib_card));
+        assert_eq!(Value::Null, decoded_value(&zlib[..].into(), &card(1.0, "zlib compressed", families::Family::ZLIB)));
+    }
+
+    #[test]
+    fn csv_rows_are_keyed_by_header() {
+        let csv = card(1.0, "CSV", families::Family::CSV);
+        assert_eq!(json!([{"a": "1", "b": "2"}]), decoded_value(&"a,b\n1,2".into(), &csv));
+        assert_eq!(json!([["1", "2"], ["3", "4"]]), decoded_value(&"1,2\n3,4".into(), &csv));
+    }
+
+    #[test]
+    fn url_is_split_into_its_parts() {
+        let url = card(1.0, "URL", families::Family::URL);
+        assert_eq!(json!({"scheme": "https", "host": "example.com", "port": 443, "path": ["a"], "query": [["b", "c d"]], "fragment": null}),
+                   decoded_value(&"https://example.com/a?b=c+d".into(), &url));
+    }
+}
@@ -0,0 +1,70 @@
+// one level of detection, and whatever was found nested inside it
+
+// path (None if this is the whole decoded payload of the parent, otherwise a JSON pointer)
+// report cards (every strategy's opinion, best first)
+// children (recognised payloads peeled off this layer)
+
+use ReportCard;
+use input::Input;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Layer {
+    pub path: Option<String>,
+    pub input: Input,
+    pub report_cards: Vec<ReportCard>,
+    pub children: Vec<Layer>,
+}
+
+impl Layer {
+    pub fn new(path: Option<String>, input: Input, report_cards: Vec<ReportCard>) -> Self {
+        Layer {
+            path,
+            input,
+            report_cards,
+            children: vec![],
+        }
+    }
+
+    pub fn top(&self) -> &ReportCard {
+        &self.report_cards[0]
+    }
+
+    // the child that is this layer decoded, if there is one
+    pub fn decoded(&self) -> Option<&Layer> {
+        self.children.iter().find(|c| c.path.is_none())
+    }
+
+    // children found inside individual fields of this layer
+    pub fn fields(&self) -> Vec<&Layer> {
+        self.children.iter().filter(|c| c.path.is_some()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use families;
+
+    fn card(family: families::Family) -> ReportCard {
+        ReportCard::new(1.0, "friend".into(), family)
+    }
+
+    #[test]
+    fn top() {
+        let layer = Layer::new(None,
+                               "hello".into(),
+                               vec![card(families::Family::JSON), card(families::Family::BASE64)]);
+        assert_eq!(layer.top().family, families::Family::JSON);
+    }
+
+    #[test]
+    fn decoded_and_fields() {
+        let mut layer = Layer::new(None, "{}".into(), vec![card(families::Family::JSON)]);
+        let field = Layer::new(Some("/a".into()), "aGk=".into(), vec![card(families::Family::BASE64)]);
+        let whole = Layer::new(None, "hi".into(), vec![card(families::Family::UNSTRUCTURED)]);
+        layer.children = vec![field.clone(), whole.clone()];
+
+        assert_eq!(layer.decoded(), Some(&whole));
+        assert_eq!(layer.fields(), vec![&field]);
+    }
+}
@@ -0,0 +1,420 @@
+// recognise binary file types from the first few bytes
+
+use families::Family;
+use chrono::{TimeZone, Utc};
+use serde_json::{self, Value};
+use std::convert::TryFrom;
+
+// signature, offset it appears at, the family and a friendly name
+const SIGNATURES: &[(&[u8], usize, Family, &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", 0, Family::PNG, "PNG image"),
+    (b"\xff\xd8\xff", 0, Family::JPEG, "JPEG image"),
+    (b"GIF87a", 0, Family::GIF, "GIF image"),
+    (b"GIF89a", 0, Family::GIF, "GIF image"),
+    (b"%PDF-", 0, Family::PDF, "PDF document"),
+    (b"PK\x03\x04", 0, Family::ZIP, "ZIP archive"),
+    (b"PK\x05\x06", 0, Family::ZIP, "ZIP archive"),
+    (b"\x1f\x8b", 0, Family::GZIP, "gzip data"),
+    (b"\x28\xb5\x2f\xfd", 0, Family::ZSTD, "zstd data"),
+    (b"BZh", 0, Family::BZIP2, "bzip2 data"),
+    (b"\xfd7zXZ\x00", 0, Family::XZ, "xz data"),
+    (b"\x7fELF", 0, Family::ELF, "ELF binary"),
+    (b"\x00asm", 0, Family::WASM, "WebAssembly module"),
+    (b"SQLite format 3\x00", 0, Family::SQLITE, "SQLite database"),
+    (b"\xac\xed\x00\x05", 0, Family::JAVA_SERIALIZED, "Java serialized object"),
+    (b"PAR1", 0, Family::PARQUET, "Parquet file"),
+    (b"Obj\x01", 0, Family::AVRO, "Avro container file"),
+    (b"\x04\x22\x4d\x18", 0, Family::LZ4, "LZ4 frame"),
+];
+
+fn find(bytes: &[u8]) -> Option<&'static (&'static [u8], usize, Family, &'static str)> {
+    SIGNATURES.iter()
+              .find(|&&(signature, offset, _, _)| bytes.len() >= offset + signature.len() && &bytes[offset..offset + signature.len()] == signature)
+}
+
+// where the signature is, how long it is, and what it means
+pub fn locate(bytes: &[u8]) -> Option<(usize, usize, &'static str)> {
+    find(bytes).map(|&(signature, offset, _, name)| (offset, signature.len(), name))
+}
+
+pub fn identify(bytes: &[u8]) -> Option<&'static str> {
+    locate(bytes).map(|(_, _, name)| name)
+}
+
+pub fn family(bytes: &[u8]) -> Option<Family> {
+    find(bytes).map(|&(_, _, family, _)| family)
+}
+
+pub fn name(family: Family) -> &'static str {
+    SIGNATURES.iter()
+              .find(|&&(_, _, f, _)| f == family)
+              .map(|&(_, _, _, name)| name)
+              .unwrap_or("binary data")
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from(b[0]) << 8 | u16::from(b[1]))
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at + 2).map(|b| u16::from(b[1]) << 8 | u16::from(b[0]))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes.get(at..at + 4).map(|b| b.iter().fold(0, |n, b| n << 8 | u32::from(*b)))
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes.get(at..at + 4).map(|b| b.iter().rev().fold(0, |n, b| n << 8 | u32::from(*b)))
+}
+
+fn png(bytes: &[u8]) -> Option<Vec<String>> {
+    if bytes.get(12..16) != Some(b"IHDR") {
+        return None;
+    }
+
+    let colour = match bytes.get(25)? {
+        0 => "greyscale",
+        2 => "RGB",
+        3 => "indexed colour",
+        4 => "greyscale with alpha",
+        6 => "RGBA",
+        _ => return None,
+    };
+
+    Some(vec![format!("{}x{} pixels", u32_be(bytes, 16)?, u32_be(bytes, 20)?),
+              format!("{} bit {}", bytes[24], colour)])
+}
+
+// the dimensions are in the first start of frame segment
+fn jpeg(bytes: &[u8]) -> Option<Vec<String>> {
+    let mut at = 2;
+
+    while let (Some(0xff), Some(&marker)) = (bytes.get(at), bytes.get(at + 1)) {
+        match marker {
+            0xc0..=0xcf if marker != 0xc4 && marker != 0xc8 && marker != 0xcc => {
+                return Some(vec![format!("{}x{} pixels", u16_be(bytes, at + 7)?, u16_be(bytes, at + 5)?)]);
+            }
+            0xd9 | 0xda => break,
+            _ => at += 2 + usize::from(u16_be(bytes, at + 2)?),
+        }
+    }
+
+    None
+}
+
+fn gif(bytes: &[u8]) -> Option<Vec<String>> {
+    Some(vec![format!("{}x{} pixels", u16_le(bytes, 6)?, u16_le(bytes, 8)?),
+              format!("version {}", String::from_utf8_lossy(&bytes[3..6]))])
+}
+
+fn pdf(bytes: &[u8]) -> Option<Vec<String>> {
+    match bytes.get(5..8)? {
+        &[major, b'.', minor] if major.is_ascii_digit() && minor.is_ascii_digit() => {
+            Some(vec![format!("version {}.{}", major as char, minor as char)])
+        }
+        _ => None,
+    }
+}
+
+fn zip(bytes: &[u8]) -> Option<Vec<String>> {
+    if bytes[2..4] == *b"\x05\x06" {
+        return Some(vec!["empty archive".to_owned()]);
+    }
+
+    let name_length = usize::from(u16_le(bytes, 26)?);
+    let name = bytes.get(30..30 + name_length)?;
+    Some(vec![format!("first entry is '{}'", String::from_utf8_lossy(name))])
+}
+
+fn gzip(bytes: &[u8]) -> Option<Vec<String>> {
+    // 8 is deflate, the only method there has ever been
+    if bytes.get(2) != Some(&8) {
+        return None;
+    }
+
+    let flags = *bytes.get(3)?;
+    let mut notes = vec![];
+
+    match u32_le(bytes, 4)? {
+        0 => {}
+        mtime => {
+            if let Some(when) = Utc.timestamp_opt(i64::from(mtime), 0).single() {
+                notes.push(format!("modified {}", when.to_rfc3339()));
+            }
+        }
+    }
+
+    // the original name follows the header and any extra field
+    if flags & 0x08 != 0 {
+        let start = match flags & 0x04 {
+            0 => 10,
+            _ => 12 + usize::from(u16_le(bytes, 10)?),
+        };
+        let name = bytes.get(start..)?;
+        let end = name.iter().position(|b| *b == 0)?;
+        notes.push(format!("original name '{}'", String::from_utf8_lossy(&name[..end])));
+    }
+
+    Some(notes)
+}
+
+fn bzip2(bytes: &[u8]) -> Option<Vec<String>> {
+    match bytes.get(3)? {
+        size @ b'1'..=b'9' => Some(vec![format!("{}00k blocks", *size as char)]),
+        _ => None,
+    }
+}
+
+fn xz(bytes: &[u8]) -> Option<Vec<String>> {
+    let check = match (bytes.get(6)?, bytes.get(7)?) {
+        (0, 0x00) => "no",
+        (0, 0x01) => "CRC32",
+        (0, 0x04) => "CRC64",
+        (0, 0x0a) => "SHA-256",
+        _ => return None,
+    };
+
+    Some(vec![format!("{} integrity checks", check)])
+}
+
+type ReadU16 = fn(&[u8], usize) -> Option<u16>;
+
+fn elf(bytes: &[u8]) -> Option<Vec<String>> {
+    let (bits, read_u16): (&str, ReadU16) = match (bytes.get(4)?, bytes.get(5)?) {
+        (1, 1) => ("32-bit little endian", u16_le),
+        (2, 1) => ("64-bit little endian", u16_le),
+        (1, 2) => ("32-bit big endian", u16_be),
+        (2, 2) => ("64-bit big endian", u16_be),
+        _ => return None,
+    };
+
+    let kind = match read_u16(bytes, 16)? {
+        1 => "relocatable object",
+        2 => "executable",
+        3 => "shared object",
+        4 => "core dump",
+        _ => "file",
+    };
+
+    let machine = match read_u16(bytes, 18)? {
+        0x03 => "x86",
+        0x08 => "MIPS",
+        0x14 => "PowerPC",
+        0x15 => "PowerPC64",
+        0x28 => "ARM",
+        0x3e => "x86-64",
+        0xb7 => "AArch64",
+        0xf3 => "RISC-V",
+        _ => "an unknown machine",
+    };
+
+    Some(vec![format!("{} {} for {}", bits, kind, machine)])
+}
+
+fn wasm(bytes: &[u8]) -> Option<Vec<String>> {
+    Some(vec![format!("version {}", u32_le(bytes, 4)?)])
+}
+
+fn sqlite(bytes: &[u8]) -> Option<Vec<String>> {
+    // 1 stands for 65536, which doesn't fit
+    let page_size = match u16_be(bytes, 16)? {
+        1 => 65536,
+        size if size.is_power_of_two() && size >= 512 => u32::from(size),
+        _ => return None,
+    };
+
+    let mut notes = vec![format!("{} byte pages", page_size)];
+
+    match u32_be(bytes, 56) {
+        Some(1) => notes.push("UTF-8 text".to_owned()),
+        Some(2) => notes.push("UTF-16le text".to_owned()),
+        Some(3) => notes.push("UTF-16be text".to_owned()),
+        _ => {}
+    }
+
+    Some(notes)
+}
+
+// the class of the first object, when it starts with a new class description
+fn java_serialized(bytes: &[u8]) -> Option<Vec<String>> {
+    if bytes.get(4..6) != Some(b"\x73\x72") {
+        return Some(vec![]);
+    }
+
+    let length = usize::from(u16_be(bytes, 6)?);
+    let class = bytes.get(8..8 + length)?;
+    Some(vec![format!("an instance of {}", String::from_utf8_lossy(class))])
+}
+
+// parquet files end with the same magic number, after the length of the footer
+fn parquet(bytes: &[u8]) -> Option<Vec<String>> {
+    if bytes.len() < 12 || !bytes.ends_with(b"PAR1") {
+        return None;
+    }
+
+    Some(vec![format!("{} byte footer", u32_le(bytes, bytes.len() - 8)?)])
+}
+
+// avro longs are zigzag encoded varints
+fn avro_long(bytes: &[u8], at: &mut usize) -> Option<i64> {
+    let mut value: u64 = 0;
+
+    for shift in 0..10 {
+        let byte = *bytes.get(*at)?;
+        *at += 1;
+        value |= u64::from(byte & 0x7f) << (shift * 7);
+
+        if byte & 0x80 == 0 {
+            return Some((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+
+    None
+}
+
+fn avro_bytes<'a>(bytes: &'a [u8], at: &mut usize) -> Option<&'a [u8]> {
+    let length = avro_long(bytes, at)?;
+    let start = *at;
+    *at += usize::try_from(length).ok()?;
+    bytes.get(start..*at)
+}
+
+// the header is a map of metadata, including the codec and the schema
+fn avro(bytes: &[u8]) -> Option<Vec<String>> {
+    let mut at = 4;
+    let mut notes = vec![];
+
+    // a negative count is followed by the block's size in bytes
+    let mut count = avro_long(bytes, &mut at)?;
+    if count < 0 {
+        avro_long(bytes, &mut at)?;
+        count = count.checked_neg()?;
+    }
+
+    for _ in 0..count {
+        let key = avro_bytes(bytes, &mut at)?;
+        let value = avro_bytes(bytes, &mut at)?;
+
+        match key {
+            b"avro.codec" => notes.push(format!("{} codec", String::from_utf8_lossy(value))),
+            b"avro.schema" => {
+                let schema: Value = serde_json::from_slice(value).ok()?;
+                if let Some(name) = schema.get("name").and_then(Value::as_str) {
+                    notes.push(format!("{} schema", name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(notes)
+}
+
+// what can cheaply be read from the header, or None if it doesn't make sense for the family
+pub fn details(family: Family, bytes: &[u8]) -> Option<Vec<String>> {
+    match family {
+        Family::PNG => png(bytes),
+        Family::JPEG => jpeg(bytes),
+        Family::GIF => gif(bytes),
+        Family::PDF => pdf(bytes),
+        Family::ZIP => zip(bytes),
+        Family::GZIP => gzip(bytes),
+        Family::BZIP2 => bzip2(bytes),
+        Family::XZ => xz(bytes),
+        Family::ELF => elf(bytes),
+        Family::WASM => wasm(bytes),
+        Family::SQLITE => sqlite(bytes),
+        Family::JAVA_SERIALIZED => java_serialized(bytes),
+        Family::PARQUET => parquet(bytes),
+        Family::AVRO => avro(bytes),
+        _ => Some(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_signatures() {
+        assert_eq!(Some("PNG image"), identify(b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR"));
+        assert_eq!(Some("gzip data"), identify(b"\x1f\x8b\x08\x00"));
+        assert_eq!(Some("PDF document"), identify(b"%PDF-1.4"));
+        assert_eq!(Some(Family::SQLITE), family(b"SQLite format 3\x00\x10\x00"));
+        assert_eq!("WebAssembly module", name(Family::WASM));
+    }
+
+    #[test]
+    fn signatures_are_located() {
+        assert_eq!(Some((0, 8, "PNG image")), locate(b"\x89PNG\r\n\x1a\n\x00"));
+        assert_eq!(None, locate(b"hello"));
+    }
+
+    #[test]
+    fn unknown_or_short() {
+        assert_eq!(None, identify(b"hello world"));
+        assert_eq!(None, identify(b"\x89PN"));
+        assert_eq!(None, identify(b""));
+    }
+
+    #[test]
+    fn every_file_type_has_a_signature() {
+        for family in Family::FILE_TYPES.iter() {
+            assert!(SIGNATURES.iter().any(|s| s.2 == *family), "{:?} has no signature", family);
+        }
+    }
+
+    #[test]
+    fn image_dimensions() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x02\x80\x00\x00\x01\xe0\x08\x06\x00\x00\x00";
+        assert_eq!(Some(vec!["640x480 pixels".to_owned(), "8 bit RGBA".to_owned()]), details(Family::PNG, png));
+
+        let jpeg = b"\xff\xd8\xff\xe0\x00\x04JF\xff\xc0\x00\x11\x08\x00\x10\x00\x20\x03";
+        assert_eq!(Some(vec!["32x16 pixels".to_owned()]), details(Family::JPEG, jpeg));
+        assert_eq!(None, details(Family::JPEG, b"\xff\xd8\xff\xd9"));
+
+        let gif = b"GIF89a\x0a\x00\x05\x00";
+        assert_eq!(Some(vec!["10x5 pixels".to_owned(), "version 89a".to_owned()]), details(Family::GIF, gif));
+    }
+
+    #[test]
+    fn versions_and_names() {
+        assert_eq!(Some(vec!["version 1.7".to_owned()]), details(Family::PDF, b"%PDF-1.7\n"));
+        assert_eq!(Some(vec!["version 1".to_owned()]), details(Family::WASM, b"\x00asm\x01\x00\x00\x00"));
+        assert_eq!(Some(vec!["900k blocks".to_owned()]), details(Family::BZIP2, b"BZh91AY&SY"));
+        assert_eq!(Some(vec!["original name 'a.txt'".to_owned()]),
+                   details(Family::GZIP, b"\x1f\x8b\x08\x08\x00\x00\x00\x00\x00\x03a.txt\x00"));
+        assert_eq!(Some(vec!["an instance of java.util.Date".to_owned()]),
+                   details(Family::JAVA_SERIALIZED, b"\xac\xed\x00\x05\x73\x72\x00\x0ejava.util.Date"));
+    }
+
+    #[test]
+    fn executables() {
+        let mut elf = b"\x7fELF\x02\x01\x01".to_vec();
+        elf.resize(16, 0);
+        elf.extend_from_slice(b"\x03\x00\x3e\x00");
+        assert_eq!(Some(vec!["64-bit little endian shared object for x86-64".to_owned()]), details(Family::ELF, &elf));
+    }
+
+    #[test]
+    fn avro_metadata() {
+        let schema = br#"{"type":"record","name":"User","fields":[]}"#;
+        let mut avro = b"Obj\x01\x04\x14avro.codec\x0edeflate\x16avro.schema".to_vec();
+        avro.push(schema.len() as u8 * 2);
+        avro.extend_from_slice(schema);
+        avro.push(0);
+
+        assert_eq!(Some(vec!["deflate codec".to_owned(), "User schema".to_owned()]), details(Family::AVRO, &avro));
+    }
+
+    #[test]
+    fn nonsense_headers() {
+        assert_eq!(None, details(Family::GZIP, b"\x1f\x8b\x63\x00"));
+        assert_eq!(None, details(Family::PARQUET, b"PAR1 is not at the end"));
+        assert_eq!(None, details(Family::PDF, b"%PDF-xyz"));
+        assert_eq!(None, details(Family::BZIP2, b"BZh hello"));
+        assert_eq!(None, details(Family::PNG, b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(None, details(Family::AVRO, b"Obj\x01\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01\x00"));
+    }
+}
@@ -3,17 +3,57 @@ extern crate serde_json;
 extern crate base64;
 extern crate regex;
 extern crate clap;
+extern crate chrono;
+extern crate jsonwebtoken;
+extern crate jsonschema;
+extern crate toml;
+extern crate flate2;
+extern crate bzip2;
+extern crate zstd;
+extern crate lz4_flex;
+extern crate data_encoding;
+extern crate bs58;
+extern crate z85;
+extern crate url;
+extern crate percent_encoding;
+extern crate quick_xml;
+extern crate roxmltree;
+extern crate serde;
+extern crate serde_yaml;
+extern crate csv;
 
 mod presenter;
 mod strategies;
 mod report_card;
 mod checker;
 mod scrutey;
+mod decoder;
+mod layer;
+mod options;
+mod verifier;
+mod iglu;
+mod magic;
+mod json_presenter;
+mod colour;
+mod highlighter;
+mod theme;
+mod config;
+mod input;
+mod hexdump;
+mod decompress;
+mod xml;
+mod yaml;
+mod delimited;
 
 pub mod families;
 pub use self::report_card::ReportCard;
-use clap::{Arg, App};
+use clap::{Arg, App, ArgMatches};
 use checker::NaiveChecker;
+use options::{Options, OutputFormat};
+use verifier::Keys;
+use iglu::Registry;
+use colour::{ColourChoice, Palette};
+use config::Config;
 
 const EXIT_SUCCESS: i32 = 0;
 const EXIT_FAILURE: i32 = 1;
@@ -22,22 +62,24 @@ const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 const APP_NAME: &'static str = env!("CARGO_PKG_NAME");
 const ABOUT: &'static str = "Scrutinize and pretty print formatted data";
 
-use std::io::{self, Read, ErrorKind};
+use std::io::{self, Read, ErrorKind, IsTerminal};
+use std::env;
 use std::fs::File;
 use std::error::Error;
+use std::path::Path;
 
-fn get_stdin_util_break() -> Result<String, String> {
+fn get_stdin_util_break() -> Result<Vec<u8>, String> {
 
-    let mut buffer = String::new();
+    let mut buffer = vec![];
 
     io::stdin()
-        .read_to_string(&mut buffer)
+        .read_to_end(&mut buffer)
         .map_err(|e| e.description().to_owned() )?;
         
     Ok(buffer)
 }
 
-fn read_file_to_string(filename: &str) -> Result<String,String> {
+fn read_file(filename: &str) -> Result<Vec<u8>,String> {
 
     let mut file = try!(File::open(filename)
                             .map_err(|e| {
@@ -50,14 +92,116 @@ fn read_file_to_string(filename: &str) -> Result<String,String> {
                                 }
                             }));
 
-    let mut contents = String::new();
+    let mut contents = vec![];
 
-    try!(file.read_to_string(&mut contents)
+    try!(file.read_to_end(&mut contents)
              .map_err(|e| e.description().to_owned()));
 
     Ok(contents)
 }
 
+// keys and config have to be text, unlike the input
+fn read_file_to_string(filename: &str) -> Result<String,String> {
+    String::from_utf8(read_file(filename)?)
+        .map_err(|_| format!("file '{}' isn't UTF-8 text", filename))
+}
+
+fn max_depth(arg: Option<&str>) -> Result<usize, String> {
+    match arg {
+        Some(s) => s.parse::<usize>()
+                    .map_err(|_| format!("max depth must be a whole number, got '{}'", s)),
+        None => Ok(options::DEFAULT_MAX_DEPTH)
+    }
+}
+
+fn output_format(arg: Option<&str>) -> Result<OutputFormat, String> {
+    match arg {
+        None | Some("text") => Ok(OutputFormat::Text),
+        Some("json") => Ok(OutputFormat::Json),
+        Some(s) => Err(format!("output must be 'text' or 'json', got '{}'", s))
+    }
+}
+
+fn colour_choice(arg: Option<&str>, no_colour: bool) -> Result<ColourChoice, String> {
+    match arg {
+        _ if no_colour => Ok(ColourChoice::Never),
+        Some(s) => colour::parse_choice(s),
+        None => Ok(ColourChoice::Auto)
+    }
+}
+
+// an explicit config file has to exist, the default one doesn't
+fn read_config(filename: Option<&str>) -> Result<Config, String> {
+    let path = match filename {
+        Some(f) => Some(f.to_owned()),
+        None => config::default_path(env::var("XDG_CONFIG_HOME").ok(), env::var("HOME").ok())
+                    .filter(|p| p.is_file())
+                    .map(|p| p.to_string_lossy().into_owned()),
+    };
+
+    match path {
+        Some(p) => config::parse(&read_file_to_string(&p)?).map_err(|e| format!("config file '{}' - {}", p, e)),
+        None => Ok(Config::default())
+    }
+}
+
+fn palette(choice: ColourChoice, config: &Config, theme: Option<&str>) -> Result<Palette, String> {
+    let theme = config.theme(theme)?;
+    let no_color = env::var("NO_COLOR").ok();
+
+    if !colour::enabled(choice, io::stdout().is_terminal(), no_color.as_deref()) {
+        return Ok(Palette::plain());
+    }
+
+    let depth = config.depth.unwrap_or_else(|| {
+        theme::detect_depth(env::var("COLORTERM").ok().as_deref(), env::var("TERM").ok().as_deref())
+    });
+
+    Ok(Palette::new(theme, depth))
+}
+
+fn read_keys(secret: Option<&str>, public_key: Option<&str>, jwks: Option<&str>) -> Result<Keys, String> {
+    let public_key_pem = match public_key {
+        Some(filename) => Some(read_file_to_string(filename)?),
+        None => None
+    };
+
+    let jwks = match jwks {
+        Some(filename) => Some(verifier::parse_jwks(&read_file_to_string(filename)?)?),
+        None => None
+    };
+
+    Ok(Keys {
+        secret: secret.map(|s| s.to_owned()),
+        public_key_pem,
+        jwks,
+    })
+}
+
+fn iglu_registry(dir: Option<&str>) -> Result<Option<Registry>, String> {
+    match dir {
+        Some(d) if Path::new(d).is_dir() => Ok(Some(Registry::new(d))),
+        Some(d) => Err(format!("iglu repo '{}' is not a directory", d)),
+        None => Ok(None)
+    }
+}
+
+fn build_options(args: &ArgMatches) -> Result<Options, String> {
+    let mut options = Options::new(max_depth(args.value_of("max-depth"))?);
+
+    options.keys = read_keys(args.value_of("secret"), args.value_of("public-key"), args.value_of("jwks"))?;
+    options.iglu_registry = iglu_registry(args.value_of("iglu-repo"))?;
+    options.explain = args.is_present("explain");
+    options.output = output_format(args.value_of("output"))?;
+    options.hexdump = args.is_present("hexdump");
+
+    let config = read_config(args.value_of("config"))?;
+    let choice = colour_choice(args.value_of("colour"), args.is_present("no-colour"))?;
+    options.palette = palette(choice, &config, args.value_of("theme"))?;
+
+    Ok(options)
+}
+
 fn real_main() -> i32 {
     
     let args = App::new(APP_NAME)
@@ -66,14 +210,79 @@ fn real_main() -> i32 {
                     .arg(Arg::with_name("INPUT")
                             .help("Sets the input file to use. Will use STDIN if no INPUT file is given")
                             .index(1))
+                    .arg(Arg::with_name("max-depth")
+                            .long("max-depth")
+                            .value_name("DEPTH")
+                            .help("How many layers of decoded payloads to dig through (default 8)")
+                            .takes_value(true))
+                    .arg(Arg::with_name("secret")
+                            .long("secret")
+                            .value_name("SECRET")
+                            .help("Shared secret used to verify HS256/HS384/HS512 JSON Web Tokens")
+                            .takes_value(true))
+                    .arg(Arg::with_name("public-key")
+                            .long("public-key")
+                            .value_name("PEM_FILE")
+                            .help("PEM encoded public key used to verify RS*, PS*, ES* or EdDSA JSON Web Tokens")
+                            .takes_value(true))
+                    .arg(Arg::with_name("jwks")
+                            .long("jwks")
+                            .value_name("JWKS_FILE")
+                            .help("Local JSON Web Key Set used to verify JSON Web Tokens")
+                            .takes_value(true))
+                    .arg(Arg::with_name("iglu-repo")
+                            .long("iglu-repo")
+                            .value_name("DIR")
+                            .help("Static Iglu registry used to validate self-describing JSON, laid out as schemas/vendor/name/jsonschema/1-0-0")
+                            .takes_value(true))
+                    .arg(Arg::with_name("explain")
+                            .long("explain")
+                            .alias("all")
+                            .help("Also list every strategy's report card, ranked, with the reasons for each score"))
+                    .arg(Arg::with_name("output")
+                            .long("output")
+                            .value_name("FORMAT")
+                            .help("'text' to describe the input, or 'json' for the whole analysis as a JSON document (default text)")
+                            .takes_value(true))
+                    .arg(Arg::with_name("hexdump")
+                            .long("hexdump")
+                            .help("Show what was decoded as a hex dump, even if it is text"))
+                    .arg(Arg::with_name("colour")
+                            .long("colour")
+                            .alias("color")
+                            .value_name("WHEN")
+                            .help("'always', 'never' or 'auto' to colour only when writing to a terminal and NO_COLOR isn't set (default auto)")
+                            .takes_value(true))
+                    .arg(Arg::with_name("no-colour")
+                            .long("no-colour")
+                            .alias("no-color")
+                            .help("Same as --colour never"))
+                    .arg(Arg::with_name("theme")
+                            .long("theme")
+                            .value_name("THEME")
+                            .help("Colour theme: default, light, dark or one defined in the config file")
+                            .takes_value(true))
+                    .arg(Arg::with_name("config")
+                            .long("config")
+                            .value_name("CONFIG_FILE")
+                            .help("TOML config file with colour settings (default ~/.config/scrutey/config.toml)")
+                            .takes_value(true))
                     .get_matches();
 
+    let options = match build_options(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprint!("{}", e);
+            return EXIT_FAILURE;
+        }
+    };
+
     let input = match args.value_of("INPUT") {
-        Some(s) => read_file_to_string(s),
+        Some(s) => read_file(s),
         None => get_stdin_util_break()
     };
 
-    let result = scrutey::scrutinize(input, &NaiveChecker::new());
+    let result = scrutey::scrutinize(input, &NaiveChecker::new_with_options(&options), &options);
 
     match result {
         Ok(values) => {
@@ -118,6 +327,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn binary_file_read() {
+        let png = read_file("tests/resources/tiny.png").expect("bad test config");
+        assert_eq!(&png[..4], b"\x89PNG");
+
+        assert_eq!(Err("file 'tests/resources/tiny.png' isn't UTF-8 text".to_owned()),
+                   read_file_to_string("tests/resources/tiny.png"));
+    }
+
+    #[test]
+    fn max_depth_parsing() {
+        assert_eq!(Ok(options::DEFAULT_MAX_DEPTH), max_depth(None));
+        assert_eq!(Ok(3), max_depth(Some("3")));
+        assert_eq!(Err("max depth must be a whole number, got 'lots'".to_owned()), max_depth(Some("lots")));
+    }
+
+    #[test]
+    fn output_format_parsing() {
+        assert_eq!(Ok(OutputFormat::Text), output_format(None));
+        assert_eq!(Ok(OutputFormat::Json), output_format(Some("json")));
+        assert_eq!(Err("output must be 'text' or 'json', got 'xml'".to_owned()), output_format(Some("xml")));
+    }
+
+    #[test]
+    fn colour_choice_parsing() {
+        assert_eq!(Ok(ColourChoice::Auto), colour_choice(None, false));
+        assert_eq!(Ok(ColourChoice::Always), colour_choice(Some("always"), false));
+        assert_eq!(Ok(ColourChoice::Never), colour_choice(Some("always"), true));
+        assert!(colour_choice(Some("sometimes"), false).is_err());
+    }
+
+    #[test]
+    fn config_files() {
+        let config = read_config(Some("tests/resources/config.toml")).expect("bad test config");
+        assert_eq!(Some("mine".to_owned()), config.theme);
+        assert!(config.themes.contains_key("mine"));
+
+        assert_eq!(Err("file 'nope.toml' not found".to_owned()), read_config(Some("nope.toml")));
+        assert!(read_config(Some("tests/resources/good_hello_world"))
+                    .unwrap_err()
+                    .starts_with("config file 'tests/resources/good_hello_world' - "));
+    }
+
+    #[test]
+    fn themes_are_checked_even_without_colour() {
+        assert_eq!(Ok(Palette::plain()), palette(ColourChoice::Never, &Config::default(), Some("dark")));
+        assert!(palette(ColourChoice::Never, &Config::default(), Some("neon")).is_err());
+    }
+
+    #[test]
+    fn keys_are_read() {
+        let keys = read_keys(Some("secret"), Some("tests/resources/rs256_public.pem"), Some("tests/resources/rs256_jwks.json"))
+            .expect("bad test config");
+
+        assert_eq!(Some("secret".to_owned()), keys.secret);
+        assert_eq!(Some(include_str!("../tests/resources/rs256_public.pem").to_owned()), keys.public_key_pem);
+        assert_eq!(1, keys.jwks.expect("jwks should parse").keys.len());
+    }
+
+    #[test]
+    fn missing_key_files_are_errors() {
+        assert_eq!(Err("file 'nope.pem' not found".to_owned()), read_keys(None, Some("nope.pem"), None));
+        assert!(read_keys(None, None, Some("tests/resources/good_hello_world")).is_err());
+    }
+
+    #[test]
+    fn iglu_repo_must_be_a_directory() {
+        assert_eq!(Ok(None), iglu_registry(None));
+        assert_eq!(Ok(Some(Registry::new("tests/resources/iglu"))), iglu_registry(Some("tests/resources/iglu")));
+        assert_eq!(Err("iglu repo 'tests/resources/good_hello_world' is not a directory".to_owned()),
+                   iglu_registry(Some("tests/resources/good_hello_world")));
+    }
+
     #[test]
     fn good_file_read() {
         let helloworld = read_file_to_string("tests/resources/good_hello_world"); 
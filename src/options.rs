@@ -0,0 +1,56 @@
+// settings collected from the command line that change how scrutey digs into input
+
+use verifier::Keys;
+use iglu::Registry;
+use colour::Palette;
+
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+// prose for people, or a JSON document for other programs
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+    pub max_depth: usize,
+    pub keys: Keys,
+    pub iglu_registry: Option<Registry>,
+    pub explain: bool,
+    pub output: OutputFormat,
+    pub palette: Palette,
+    pub hexdump: bool,
+}
+
+impl Options {
+    pub fn new(max_depth: usize) -> Self {
+        Options {
+            max_depth,
+            keys: Keys::default(),
+            iglu_registry: None,
+            explain: false,
+            output: OutputFormat::Text,
+            palette: Palette::plain(),
+            hexdump: false,
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::new(DEFAULT_MAX_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(Options::default().max_depth, DEFAULT_MAX_DEPTH);
+        assert_eq!(Options::default().output, OutputFormat::Text);
+    }
+}
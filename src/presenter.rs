@@ -1,50 +1,515 @@
 use ReportCard;
+use report_card::{Factor, ParseError};
 use serde_json::{self, Value};
 use families::Family;
-use base64;
+use decoder::{self, Address, Jwt};
+use layer::Layer;
+use chrono::{TimeZone, Utc};
+use iglu;
+use colour::{Palette, Role};
+use highlighter;
+use hexdump;
+use xml::{self, Node};
+use yaml;
+use toml::{self, Table};
+use delimited;
+use input::Input;
+use std::str;
 
-fn get_header(t: &str) -> String {
-    format!("Scrutey thinks this is {}:\n\n", t)
+fn get_header(t: &str, palette: &Palette) -> String {
+    format!("{} {}:\n\n", palette.paint(Role::Header, "Scrutey thinks this is"), palette.paint(Role::Name, t))
 }
 
-fn get_response(friendly_name: &str, body: &str) -> String {
-    format!("{}{}", get_header(friendly_name), body)
+fn get_response(friendly_name: &str, body: &str, palette: &Palette) -> String {
+    format!("{}{}", get_header(friendly_name, palette), body)
 }
 
-pub fn present(input: &str, report_card: &Option<ReportCard>) -> String {
+fn get_field_header(path: &str, t: &str, palette: &Palette) -> String {
+    format!("{} {}:\n\n", palette.paint(Role::Header, &format!("Scrutey thinks {} is", path)), palette.paint(Role::Name, t))
+}
+
+fn nonsense(input: &str, palette: &Palette) -> String {
+    get_response("nonsense", input, palette)
+}
 
-    fn nonsense(input: &str) -> String {
-        get_response("nonsense", input)
+fn plural(n: i64, unit: &str) -> String {
+    if n == 1 {
+        format!("{} {}", n, unit)
+    } else {
+        format!("{} {}s", n, unit)
     }
+}
 
-    if let Some(ref card) = *report_card {
+fn humanise_seconds(seconds: i64) -> String {
+    let seconds = seconds.abs();
+
+    if seconds < 60 {
+        plural(seconds, "second")
+    } else if seconds < 2 * 60 * 60 {
+        plural(seconds / 60, "minute")
+    } else if seconds < 2 * 24 * 60 * 60 {
+        plural(seconds / (60 * 60), "hour")
+    } else {
+        plural(seconds / (24 * 60 * 60), "day")
+    }
+}
+
+fn describe_claim_time(claim: &str, timestamp: i64, now: i64) -> String {
+    let ago = humanise_seconds(now - timestamp);
+    let past = timestamp <= now;
+
+    let annotation = match (claim, past) {
+        ("exp", true) => format!("expired {} ago", ago),
+        ("exp", false) => format!("expires in {}", ago),
+        ("nbf", true) => format!("became valid {} ago", ago),
+        ("nbf", false) => format!("not valid for another {}", ago),
+        (_, true) => format!("issued {} ago", ago),
+        (_, false) => format!("issued {} in the future", ago),
+    };
+
+    let when = Utc.timestamp_opt(timestamp, 0)
+                  .single()
+                  .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                  .unwrap_or_else(|| timestamp.to_string());
+
+    format!("{}: {} ({})", claim, when, annotation)
+}
+
+fn describe_jwt(jwt: &Jwt, now: i64, palette: &Palette) -> String {
+    let mut out = format!("Header:\n\n{}\n\nClaims:\n\n{}", pretty_json(&jwt.header, palette), pretty_json(&jwt.claims, palette));
+
+    let times: Vec<String> = ["iat", "nbf", "exp"].iter()
+        .filter_map(|claim| {
+            jwt.claims.get(*claim)
+                .and_then(|t| t.as_i64().or_else(|| t.as_f64().map(|f| f as i64)))
+                .map(|t| describe_claim_time(claim, t, now))
+        })
+        .collect();
+
+    if !times.is_empty() {
+        out.push_str("\n\nTimes:\n\n");
+        out.push_str(&times.join("\n"));
+    }
+
+    if jwt.header.get("alg").and_then(|a| a.as_str()).map(|a| a.eq_ignore_ascii_case("none")).unwrap_or(false) {
+        out.push_str("\n\nWARNING: alg is \"none\" - this token is unsigned and anyone could have written it");
+    }
+
+    out
+}
+
+fn pretty_json(value: &Value, palette: &Palette) -> String {
+    let pretty = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    highlighter::highlight(Family::JSON, &pretty, palette)
+}
+
+// schema details above the data, repeated for every envelope nested inside it
+fn describe_envelope(schema: &str, data: &Value, palette: &Palette) -> String {
+    let mut out = match iglu::parse(schema) {
+        Ok(key) => format!("Schema:  {}\nVendor:  {}\nName:    {}\nFormat:  {}\nVersion: {}",
+                           key, key.vendor, key.name, key.format, key.version),
+        Err(e) => format!("Schema:  {} ({})", schema, e),
+    };
+
+    let contexts: Vec<(&str, &Value)> = match *data {
+        Value::Array(ref items) if !items.is_empty() => items.iter().filter_map(iglu::envelope).collect(),
+        _ => vec![],
+    };
+
+    if let Some((schema, data)) = iglu::envelope(data) {
+        out.push_str("\n\n");
+        out.push_str(&describe_envelope(schema, data, palette));
+    } else if !contexts.is_empty() && contexts.len() == data.as_array().map(|a| a.len()).unwrap_or(0) {
+        for (schema, data) in contexts {
+            out.push_str("\n\n");
+            out.push_str(&describe_envelope(schema, data, palette));
+        }
+    } else {
+        out.push_str("\n\n");
+        out.push_str(&pretty_json(data, palette));
+    }
+
+    out
+}
+
+// namespaces declared on the root, then the document laid out again
+fn describe_xml(nodes: &[Node], palette: &Palette) -> String {
+    let document = highlighter::highlight(Family::XML, &xml::pretty(nodes), palette);
+    let namespaces = xml::namespaces(nodes);
+
+    if namespaces.is_empty() {
+        return document;
+    }
+
+    let labelled: Vec<(&str, &str)> = namespaces.iter()
+        .map(|(prefix, uri)| (if prefix.is_empty() { "(default)" } else { prefix.as_str() }, uri.as_str()))
+        .collect();
+    let width = labelled.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let declared: Vec<String> = labelled.iter()
+        .map(|(label, uri)| format!("{:width$}  {}", label, uri, width = width))
+        .collect();
+
+    format!("Namespaces:\n\n{}\n\nDocument:\n\n{}", declared.join("\n"), document)
+}
+
+// what each column holds, then the rows lined up under their headings
+fn describe_table(table: &delimited::Table) -> String {
+    let labels = delimited::labels(table);
+    let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+    let columns: Vec<String> = labels.iter()
+        .zip(table.column_types())
+        .map(|(label, column)| format!("{:width$}  {}", label.trim(), column.name(), width = width))
+        .collect();
+
+    format!("Columns:\n\n{}\n\nTable:\n\n{}", columns.join("\n"), delimited::render(table))
+}
+
+// labels in one column and values in the next
+fn aligned(pairs: &[(&str, String)]) -> String {
+    let width = pairs.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+    pairs.iter()
+         .map(|(label, value)| format!("{:width$}  {}", label, value, width = width).trim_end().to_owned())
+         .collect::<Vec<String>>()
+         .join("\n")
+}
+
+// the parts of a URL, a path segment or query parameter to a line, all percent-decoded
+fn describe_address(address: &Address) -> String {
+    let mut sections = vec![];
+
+    if let Some(ref scheme) = address.scheme {
+        let mut parts = vec![("scheme", scheme.clone())];
+        parts.extend(address.host.iter().map(|host| ("host", host.clone())));
+        parts.extend(address.port.iter().map(|port| ("port", port.to_string())));
+        parts.extend(address.fragment.iter().map(|fragment| ("fragment", fragment.clone())));
+        sections.push(format!("Address:\n\n{}", aligned(&parts)));
+    }
+
+    if !address.path.is_empty() {
+        sections.push(format!("Path:\n\n{}", address.path.join("\n")));
+    }
+
+    if !address.query.is_empty() {
+        let parameters: Vec<(&str, String)> = address.query.iter().map(|(key, value)| (key.as_str(), value.clone())).collect();
+        sections.push(format!("Query:\n\n{}", aligned(&parameters)));
+    }
+
+    sections.join("\n\n")
+}
+
+// line:col and message for every known error, if the position is known
+fn describe_errors(errors: &[ParseError]) -> String {
+    errors.iter()
+          .map(|e| if e.line > 0 { format!("{}:{} {}", e.line, e.col, e.message) } else { e.message.clone() })
+          .collect::<Vec<String>>()
+          .join("\n")
+}
+
+// lines either side of an error to show it in context
+const CONTEXT_LINES: usize = 2;
+
+// an error in the style of rustc, with the offending line, its neighbours and a caret under the column
+fn annotate_error(input: &str, error: &ParseError, palette: &Palette) -> String {
+    let lines: Vec<&str> = input.lines().collect();
+    let heading = format!("{} {}", palette.paint(Role::Error, "error:"), error.message);
+    let line = error.line as usize;
+
+    if line == 0 || line > lines.len() {
+        return heading;
+    }
+
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (line + CONTEXT_LINES).min(lines.len());
+    let width = last.to_string().len();
+    let gutter = " ".repeat(width);
+    let bar = palette.paint(Role::Gutter, "|");
+
+    // keep tabs so the caret lines up however the terminal expands them
+    let padding: String = lines[line - 1].chars()
+        .chain(std::iter::repeat(' '))
+        .take((error.col as usize).saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+
+    let mut out = vec![heading,
+                       format!("{}{} {}:{}", gutter, palette.paint(Role::Gutter, "-->"), error.line, error.col),
+                       format!("{} {}", gutter, bar)];
+
+    for n in first..=last {
+        let number = palette.paint(Role::Gutter, &format!("{:>width$}", n, width = width));
+        out.push(format!("{} {} {}", number, bar, lines[n - 1]).trim_end().to_owned());
+
+        if n == line {
+            out.push(format!("{} {} {}{}", gutter, bar, padding, palette.paint(Role::Error, "^")));
+        }
+    }
+
+    out.join("\n")
+}
 
-        match card.family {
-            Family::JSON => {
-                let p: Option<Value> = serde_json::from_str(input).ok();
-                let pretty_json: String = match p {
-                    Some(s) => serde_json::to_string_pretty(&s).unwrap_or(input.to_owned()),
-                    _ => input.to_owned(),
-                };
-                get_response(&card.type_friendly_name, &pretty_json)
+fn annotate_errors(input: &str, errors: &[ParseError], palette: &Palette) -> String {
+    errors.iter()
+          .map(|e| annotate_error(input, e, palette))
+          .collect::<Vec<String>>()
+          .join("\n\n")
+}
+
+// text that is safe to print as it is, without control characters that would upset the terminal
+fn is_displayable(text: &str) -> bool {
+    !text.chars().any(|c| c.is_control() && c != '\n' && c != '\r' && c != '\t')
+}
+
+// the bytes a hexdump is most useful for, which for base64 and friends is what they decode to
+fn dumpable_bytes(input: &Input, card: &ReportCard) -> Vec<u8> {
+    match (card.family, input.text()) {
+        (family, Some(text)) if family.is_encoding() => decoder::decode(family, text).unwrap_or_else(|| input.bytes().to_vec()),
+        _ => input.bytes().to_vec(),
+    }
+}
+
+// what compressed bytes inflate to, or the bytes themselves if they don't
+fn inflated_body(input: &Input, card: &ReportCard, palette: &Palette, force_hexdump: bool) -> String {
+    match card.inflated {
+        Some(ref inflated) => match str::from_utf8(inflated) {
+            Ok(text) if !force_hexdump && is_displayable(text) => text.to_owned(),
+            _ => hexdump::dump(inflated, palette),
+        },
+        None => hexdump::dump(input.bytes(), palette),
+    }
+}
+
+fn get_body(input: &Input, card: &ReportCard, palette: &Palette, force_hexdump: bool) -> String {
+    let body = match input.text() {
+        _ if card.family.is_compressed() => inflated_body(input, card, palette, force_hexdump),
+        Some(text) if !force_hexdump && !card.family.is_file_type() => decode_body(text, card, palette),
+        _ => hexdump::dump(&dumpable_bytes(input, card), palette),
+    };
+
+    // whatever the header said about a file, or how well it compressed, above its bytes
+    let body = if (card.family.is_file_type() || card.family.is_compressed()) && !card.notes.is_empty() {
+        format!("{}\n\n{}", card.notes.join("\n"), body)
+    } else {
+        body
+    };
+
+    if card.known_errors.is_empty() {
+        body
+    } else {
+        format!("{}\n\n{}", body, annotate_errors(&input.lossy(), &card.known_errors, palette))
+    }
+}
+
+fn decode_body(input: &str, card: &ReportCard, palette: &Palette) -> String {
+    match card.family {
+        Family::JSON => {
+            let p: Option<Value> = serde_json::from_str(input).ok();
+            match p {
+                Some(s) => pretty_json(&s, palette),
+                _ => input.to_owned(),
             }
-            Family::BASE64 => {
-                let p = base64::decode(input.trim()).ok();
-
-                if let Some(ref decoded) = p {
-                    get_response(&card.type_friendly_name,
-                                 &String::from_utf8_lossy(decoded).into_owned())
-                } else {
-                    get_response(&card.type_friendly_name, input)
+        }
+        Family::SELF_DESCRIBING_JSON => {
+            let p: Option<Value> = serde_json::from_str(input).ok();
+            match p.as_ref().and_then(iglu::envelope) {
+                Some((schema, data)) => describe_envelope(schema, data, palette),
+                None => input.to_owned(),
+            }
+        }
+        family if family.is_encoding() => {
+            match decoder::decode(family, input) {
+                Some(ref decoded) => match str::from_utf8(decoded) {
+                    Ok(text) if is_displayable(text) => highlighter::highlight(card.family, text, palette),
+                    _ => hexdump::dump(decoded, palette),
+                },
+                None => input.to_owned(),
+            }
+        }
+        Family::XML => {
+            match xml::parse(input) {
+                Ok(ref nodes) => describe_xml(nodes, palette),
+                Err(_) => input.to_owned(),
+            }
+        }
+        Family::YAML => {
+            match yaml::parse(input) {
+                Ok(ref documents) => highlighter::highlight(card.family, &yaml::pretty(documents), palette),
+                Err(_) => input.to_owned(),
+            }
+        }
+        Family::TOML => {
+            match input.parse::<Table>().map(|table| toml::to_string_pretty(&table)) {
+                Ok(Ok(ref pretty)) => highlighter::highlight(card.family, pretty.trim_end(), palette),
+                _ => input.to_owned(),
+            }
+        }
+        Family::CSV => {
+            match delimited::sniff(input) {
+                Some(ref table) => describe_table(table),
+                None => input.to_owned(),
+            }
+        }
+        Family::URL => {
+            match decoder::decode_url(input) {
+                Some(ref address) => describe_address(address),
+                None => input.to_owned(),
+            }
+        }
+        Family::JWT => {
+            match decoder::decode_jwt(input) {
+                Some(ref jwt) => {
+                    let mut body = describe_jwt(jwt, Utc::now().timestamp(), palette);
+
+                    if !card.notes.is_empty() {
+                        body.push_str("\n\nSignature:\n\n");
+                        body.push_str(&card.notes.join("\n"));
+                    }
+                    body
                 }
+                None => input.to_owned(),
             }
-            Family::UNSTRUCTURED => nonsense(input),
+        }
+        _ => input.to_owned(),
+    }
+}
+
+pub fn present(input: &Input, report_card: &Option<ReportCard>, palette: &Palette, force_hexdump: bool) -> String {
+
+    if let Some(ref card) = *report_card {
+
+        // the best of a bad lot is still nothing in particular
+        let unrecognised = card.family == Family::UNSTRUCTURED || card.sureness <= 0.0;
+
+        match (unrecognised, force_hexdump) {
+            (true, false) => nonsense(&input.lossy(), palette),
+            (true, true) => get_response("nonsense", &get_body(input, card, palette, true), palette),
+            _ => get_response(&card.type_friendly_name, &get_body(input, card, palette, force_hexdump), palette),
         }
 
     } else {
-        nonsense(input)
+        nonsense(&input.lossy(), palette)
+    }
+
+}
+
+// follow whole-payload decodes down to the innermost layer, collecting names on the way,
+// e.g. Base64 -> JSON
+fn innermost(layer: &Layer) -> (String, &Layer) {
+    let mut names = vec![layer.top().type_friendly_name.clone()];
+    let mut current = layer;
+
+    while let Some(decoded) = current.decoded() {
+        names.push(decoded.top().type_friendly_name.clone());
+        current = decoded;
+    }
+
+    (names.join(" -> "), current)
+}
+
+// how well each layer that was inflated on the way to the innermost one had compressed
+fn compression_notes(layer: &Layer) -> String {
+    let mut notes = vec![];
+    let mut current = layer;
+
+    while let Some(decoded) = current.decoded() {
+        if current.top().family.is_compressed() {
+            notes.extend(current.top().notes.iter().cloned());
+        }
+        current = decoded;
     }
 
+    notes.iter().map(|n| format!("{}\n\n", n)).collect()
+}
+
+fn present_fields(layer: &Layer, prefix: &str, out: &mut String, palette: &Palette, force_hexdump: bool) {
+    for field in layer.fields() {
+        let path = format!("{}{}", prefix, field.path.as_deref().unwrap_or(""));
+        let (name, inner) = innermost(field);
+
+        out.push_str("\n\n");
+        out.push_str(&get_field_header(&path, &name, palette));
+        out.push_str(&compression_notes(field));
+        out.push_str(&get_body(&inner.input, inner.top(), palette, force_hexdump));
+
+        present_fields(inner, &format!("{} -> ", path), out, palette, force_hexdump);
+    }
+}
+
+fn describe_factor(factor: &Factor) -> String {
+    let sign = if factor.weight > 0.0 {
+        "+"
+    } else if factor.weight < 0.0 {
+        "-"
+    } else {
+        "="
+    };
+
+    format!("{} {:.2} {}", sign, factor.weight.abs(), factor.reason)
+}
+
+// every strategy's report card, best first, with the reasons behind each score
+pub fn present_explanation(layer: &Layer, palette: &Palette) -> String {
+    let headings = ["Rank", "Sureness", "Strategy", "Name", "Family"];
+
+    let rows: Vec<[String; 5]> = layer.report_cards.iter()
+        .enumerate()
+        .map(|(i, card)| [(i + 1).to_string(),
+                          format!("{:.2}", card.sureness),
+                          card.strategy_id.clone(),
+                          card.type_friendly_name.clone(),
+                          format!("{:?}", card.family)])
+        .collect();
+
+    let widths: Vec<usize> = (0..headings.len())
+        .map(|c| rows.iter().map(|r| r[c].len()).chain(Some(headings[c].len())).max().unwrap_or(0))
+        .collect();
+
+    // padding goes outside the colour so the columns still line up
+    let line = |cells: Vec<&str>, roles: [Option<Role>; 5]| -> String {
+        cells.iter()
+             .zip(widths.iter())
+             .zip(roles.iter())
+             .map(|((cell, width), role)| {
+                 let painted = role.map(|r| palette.paint(r, cell)).unwrap_or_else(|| cell.to_string());
+                 format!("{}{}", painted, " ".repeat(width - cell.len()))
+             })
+             .collect::<Vec<String>>()
+             .join("  ")
+             .trim_end()
+             .to_owned()
+    };
+
+    let indent = " ".repeat(widths[0] + 2);
+    let mut out = vec![line(headings.to_vec(), [Some(Role::Header); 5])];
+
+    for (row, card) in rows.iter().zip(layer.report_cards.iter()) {
+        let roles = [None, Some(Role::Sureness), None, Some(Role::Name), Some(Role::Family)];
+        out.push(line(row.iter().map(|c| c.as_str()).collect(), roles));
+
+        for factor in &card.factors {
+            out.push(format!("{}{}", indent, describe_factor(factor)));
+        }
+
+        for error in &card.known_errors {
+            out.push(format!("{}{} {}", indent, palette.paint(Role::Error, "!"), describe_errors(std::slice::from_ref(error))));
+        }
+    }
+
+    format!("{}\n\n{}", palette.paint(Role::Header, "Scrutey's reasoning:"), out.join("\n"))
+}
+
+// force_hexdump shows the bytes of the innermost layers even when they are text
+pub fn present_layer(layer: &Layer, palette: &Palette, force_hexdump: bool) -> String {
+    if layer.decoded().is_none() {
+        let mut out = present(&layer.input, &Some(layer.top().clone()), palette, force_hexdump);
+        present_fields(layer, "", &mut out, palette, force_hexdump);
+        return out;
+    }
+
+    let (name, inner) = innermost(layer);
+    let body = format!("{}{}", compression_notes(layer), get_body(&inner.input, inner.top(), palette, force_hexdump));
+    let mut out = get_response(&name, &body, palette);
+    present_fields(inner, "", &mut out, palette, force_hexdump);
+    out
 }
 
 #[cfg(test)]
@@ -54,21 +519,37 @@ mod tests {
     use report_card::ReportCard;
     use families;
     use presenter;
-    use base64;
+    use layer::Layer;
 
     #[test]
     fn test_get_header() {
-        assert_eq!("Scrutey thinks this is garbage:\n\n", get_header("garbage"));
+        assert_eq!("Scrutey thinks this is garbage:\n\n", get_header("garbage", &Palette::plain()));
         assert_eq!("Scrutey thinks this is hardcore pornography:\n\n",
-                   get_header("hardcore pornography"));
+                   get_header("hardcore pornography", &Palette::plain()));
+    }
+
+    #[test]
+    fn header_is_coloured() {
+        assert_eq!("\x1b[1mScrutey thinks this is\x1b[0m \x1b[1;34mJSON\x1b[0m:\n\n", get_header("JSON", &Palette::ansi()));
+    }
+
+    #[test]
+    fn errors_are_coloured() {
+        let result = annotate_error("{", &ParseError::new("oops", 1, 2), &Palette::ansi());
+        assert_eq!("\x1b[1;31merror:\x1b[0m oops\n \
+                    \x1b[1;34m-->\x1b[0m 1:2\n  \
+                    \x1b[1;34m|\x1b[0m\n\
+                    \x1b[1;34m1\x1b[0m \x1b[1;34m|\x1b[0m {\n  \
+                    \x1b[1;34m|\x1b[0m  \x1b[1;31m^\x1b[0m",
+                   result);
     }
 
     #[test]
     fn none_is_garbage() {
         let given = "hello world";
-        let result = present(given, &None);
+        let result = present(&Input::from(given), &None, &Palette::plain(), false);
 
-        let expected = format!("{}{}", get_header("nonsense"), given);
+        let expected = format!("{}{}", get_header("nonsense", &Palette::plain()), given);
 
         assert_eq!(expected, result);
     }
@@ -86,37 +567,201 @@ mod tests {
 
         assert_ne!(sample_json_pretty, sample_json_compact);
 
-        let result = present(&sample_json_compact, &given_format);
-        let expected = format!("{}{}", get_header("JSON"), "SOME HIGHLIGHTED PRETTY PRINTED JSON SHOULD BE HERE");//sample_json_pretty);
+        let result = present(&Input::from(sample_json_compact.as_str()), &given_format, &Palette::plain(), false);
+        let expected = format!("{}{}", get_header("JSON", &Palette::plain()), sample_json_pretty);
 
         assert_eq!(expected, result);
+
+        let highlighted = present(&Input::from(sample_json_compact.as_str()), &given_format, &Palette::ansi(), false);
+        let expected = format!("{}{}",
+                               get_header("JSON", &Palette::ansi()),
+                               highlighter::highlight(families::Family::JSON, &sample_json_pretty, &Palette::ansi()));
+
+        assert_eq!(expected, highlighted);
+        assert!(highlighted.contains("\x1b[34m\"hello\"\x1b[0m: \x1b[32m\"world\"\x1b[0m"));
     }
 
     #[test]
     fn json_is_printed_as_is_if_errored() {
         let given_format = Some(ReportCard::new(1.0, "BROKENJSON".into(), families::Family::JSON));
         let sample_json = "{";
-        let result = present(&sample_json, &given_format);
-        assert_eq!(format!("{}{}", get_header("BROKENJSON"), sample_json), result)
+        let result = present(&Input::from(sample_json), &given_format, &Palette::plain(), false);
+        assert_eq!(format!("{}{}", get_header("BROKENJSON", &Palette::plain()), sample_json), result);
+
+        let with_error = Some(ReportCard::new_with_errors(0.5,
+                                                          "almost JSON".into(),
+                                                          families::Family::JSON,
+                                                          vec![ParseError::new("EOF while parsing an object", 1, 2)]));
+        let result = present(&Input::from(sample_json), &with_error, &Palette::plain(), false);
+        assert_eq!(format!("{}{{\n\nerror: EOF while parsing an object\n --> 1:2\n  |\n1 | {{\n  |  ^", get_header("almost JSON", &Palette::plain())),
+                   result);
     }
 
     #[test]
     fn good_base64_is_decoded() {
         let given_format = Some(ReportCard::new(1.0, "Base64".into(), families::Family::BASE64));
         let sample_base64 = " aGVsbG8gd29ybGQ=  ";
-        let result = present(sample_base64, &given_format);
+        let result = present(&Input::from(sample_base64), &given_format, &Palette::plain(), false);
 
-        let expected = format!("{}{}", get_header("Base64"), "hello world");
+        let expected = format!("{}{}", get_header("Base64", &Palette::plain()), "hello world");
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn base64_variants_are_decoded() {
+        for sample in ["aGk_Pz8-aGk", "aGVsbG8g\r\nd29ybGQ="].iter() {
+            let given_format = Some(ReportCard::new(1.0, "Base64".into(), families::Family::BASE64));
+            let result = present(&Input::from(*sample), &given_format, &Palette::plain(), false);
+
+            let decoded = String::from_utf8(decoder::decode_base64(sample).expect("bad test config")).expect("bad test config");
+            assert_eq!(format!("{}{}", get_header("Base64", &Palette::plain()), decoded), result);
+        }
+    }
+
+    #[test]
+    fn xml_is_reindented_below_its_namespaces() {
+        let given_format = Some(ReportCard::new(1.0, "XML".into(), families::Family::XML));
+        let xml = "<s:Envelope xmlns:s=\"urn:s\" xmlns=\"urn:d\"><s:Body><!-- hi --><a>1</a></s:Body></s:Envelope>";
+        let result = present(&Input::from(xml), &given_format, &Palette::plain(), false);
+
+        let expected = "Namespaces:\n\n\
+                        s          urn:s\n\
+                        (default)  urn:d\n\n\
+                        Document:\n\n\
+                        <s:Envelope xmlns:s=\"urn:s\" xmlns=\"urn:d\">\n  \
+                          <s:Body>\n    \
+                            <!-- hi -->\n    \
+                            <a>1</a>\n  \
+                          </s:Body>\n\
+                        </s:Envelope>";
+        assert_eq!(format!("{}{}", get_header("XML", &Palette::plain()), expected), result);
+    }
+
+    #[test]
+    fn yaml_is_normalised() {
+        let given_format = Some(ReportCard::new(1.0, "YAML".into(), families::Family::YAML));
+        let yaml = "# deploy\nsteps:\n- run:   make   # build it\n- {name: test, run: make test}\n";
+        let result = present(&Input::from(yaml), &given_format, &Palette::plain(), false);
+
+        let expected = "steps:\n\
+                        - run: make\n\
+                        - name: test\n  \
+                          run: make test";
+        assert_eq!(format!("{}{}", get_header("YAML", &Palette::plain()), expected), result);
+    }
+
+    #[test]
+    fn toml_is_in_canonical_order() {
+        let given_format = Some(ReportCard::new(1.0, "TOML".into(), families::Family::TOML));
+        let toml = "[dependencies]\nregex = \"0.2\"\n\n[package]\nversion = \"0.1.0\"\nname = \"scrutey\"\n";
+        let result = present(&Input::from(toml), &given_format, &Palette::plain(), false);
+
+        let expected = "[dependencies]\n\
+                        regex = \"0.2\"\n\n\
+                        [package]\n\
+                        name = \"scrutey\"\n\
+                        version = \"0.1.0\"";
+        assert_eq!(format!("{}{}", get_header("TOML", &Palette::plain()), expected), result);
+    }
+
+    #[test]
+    fn csv_is_tabulated_with_column_types() {
+        let given_format = Some(ReportCard::new(1.0, "CSV".into(), families::Family::CSV));
+        let csv = "id,name,score\n1,alice,9.5\n22,bob,10\n";
+        let result = present(&Input::from(csv), &given_format, &Palette::plain(), false);
+
+        let expected = "Columns:\n\n\
+                        id     integer\n\
+                        name   text\n\
+                        score  decimal\n\n\
+                        Table:\n\n\
+                        id  name   score\n\
+                        --  -----  -----\n\
+                        \x201  alice    9.5\n\
+                        22  bob       10";
+        assert_eq!(format!("{}{}", get_header("CSV", &Palette::plain()), expected), result);
+    }
+
+    #[test]
+    fn url_is_split_into_its_parts() {
+        let given_format = Some(ReportCard::new(1.0, "URL".into(), families::Family::URL));
+        let url = "https://example.com/a%20b/c?q=hello+world&empty=#top";
+        let result = present(&Input::from(url), &given_format, &Palette::plain(), false);
+
+        let expected = "Address:\n\n\
+                        scheme    https\n\
+                        host      example.com\n\
+                        port      443\n\
+                        fragment  top\n\n\
+                        Path:\n\n\
+                        a b\n\
+                        c\n\n\
+                        Query:\n\n\
+                        q      hello world\n\
+                        empty";
+        assert_eq!(format!("{}{}", get_header("URL", &Palette::plain()), expected), result);
+
+        let given_format = Some(ReportCard::new(1.0, "query string".into(), families::Family::URL));
+        let result = present(&Input::from("a=1&bb=%7B%7D"), &given_format, &Palette::plain(), false);
+        assert_eq!(format!("{}{}", get_header("query string", &Palette::plain()), "Query:\n\na   1\nbb  {}"), result);
+    }
+
     #[test]
     fn bad_base64_is_printed_as_is() {
         let given_format = Some(ReportCard::new(1.0, "Base64".into(), families::Family::BASE64));
         let sample_base64 = "!!!";
-        let result = present(sample_base64, &given_format);
+        let result = present(&Input::from(sample_base64), &given_format, &Palette::plain(), false);
+
+        let expected = format!("{}{}", get_header("Base64", &Palette::plain()), "!!!");
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn base64_of_binary_is_hexdumped() {
+        let given_format = Some(ReportCard::new(1.0, "Base64".into(), families::Family::BASE64));
+        let result = present(&Input::from("H4sIAA=="), &given_format, &Palette::plain(), false);
 
-        let expected = format!("{}{}", get_header("Base64"), "!!!");
+        let expected = format!("{}{}", get_header("Base64", &Palette::plain()), hexdump::dump(b"\x1f\x8b\x08\x00", &Palette::plain()));
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn control_characters_are_hexdumped() {
+        let given_format = Some(ReportCard::new(1.0, "Hex".into(), families::Family::HEX));
+        let result = present(&Input::from("1b5b324a"), &given_format, &Palette::plain(), false);
+
+        let expected = format!("{}{}", get_header("Hex", &Palette::plain()), hexdump::dump(b"\x1b[2J", &Palette::plain()));
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn file_types_are_described_above_their_bytes() {
+        let mut card = ReportCard::new(1.0, "PDF document".into(), families::Family::PDF);
+        card.notes.push("version 1.4".into());
+        let result = present(&Input::from("%PDF-1.4"), &Some(card), &Palette::plain(), false);
+
+        let expected = format!("{}version 1.4\n\n{}", get_header("PDF document", &Palette::plain()), hexdump::dump(b"%PDF-1.4", &Palette::plain()));
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn compressed_is_inflated() {
+        let zlib = b"x\x9c\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00\xebU\x0d\x19";
+        let mut card = ReportCard::new(1.0, "zlib compressed".into(), families::Family::ZLIB);
+        card.notes.push("17 bytes inflate with zlib to 35 bytes".into());
+        card.inflated = Some(b"hello hello hello hello hello hello".to_vec());
+        let result = present(&Input::from(&zlib[..]), &Some(card), &Palette::plain(), false);
+
+        let expected = format!("{}17 bytes inflate with zlib to 35 bytes\n\nhello hello hello hello hello hello", get_header("zlib compressed", &Palette::plain()));
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn hexdump_can_be_forced() {
+        let given_format = Some(ReportCard::new(1.0, "Base64".into(), families::Family::BASE64));
+        let result = present(&Input::from("aGk="), &given_format, &Palette::plain(), true);
+
+        let expected = format!("{}{}", get_header("Base64", &Palette::plain()), hexdump::dump(b"hi", &Palette::plain()));
         assert_eq!(expected, result);
     }
 
@@ -125,12 +770,224 @@ mod tests {
         let given_format =
             Some(ReportCard::new(1.0, "unstructured".into(), families::Family::UNSTRUCTURED));
         let given = "hello world";
-        let result = present(given, &given_format);
+        let result = present(&Input::from(given), &given_format, &Palette::plain(), false);
 
-        let expected = format!("{}{}", get_header("nonsense"), given);
+        let expected = format!("{}{}", get_header("nonsense", &Palette::plain()), given);
 
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn nothing_sure_is_nonsense() {
+        let given_format = Some(ReportCard::new(0.0, "gzip compressed".into(), families::Family::GZIP));
+        let result = present(&Input::from("potato"), &given_format, &Palette::plain(), false);
+
+        assert_eq!(format!("{}potato", get_header("nonsense", &Palette::plain())), result);
+    }
+
+    fn layer(path: Option<&str>, input: &str, name: &str, family: families::Family) -> Layer {
+        Layer::new(path.map(|p| p.to_owned()), input.into(), vec![ReportCard::new(1.0, name.into(), family)])
+    }
+
+    #[test]
+    fn single_layer_is_presented_as_before() {
+        let single = layer(None, "aGVsbG8gd29ybGQ=", "Base64", families::Family::BASE64);
+        let expected = present(&Input::from("aGVsbG8gd29ybGQ="), &Some(single.top().clone()), &Palette::plain(), false);
+        assert_eq!(expected, present_layer(&single, &Palette::plain(), false));
+    }
+
+    #[test]
+    fn decoded_layers_are_chained() {
+        let mut outer = layer(None, "eyJhIjoxfQ==", "Base64", families::Family::BASE64);
+        outer.children.push(layer(None, "{\"a\":1}", "JSON", families::Family::JSON));
+
+        let expected = format!("{}{}", get_header("Base64 -> JSON", &Palette::plain()), "{\n  \"a\": 1\n}");
+        assert_eq!(expected, present_layer(&outer, &Palette::plain(), false));
+    }
+
+    #[test]
+    fn fields_are_listed_after_the_body() {
+        let mut outer = layer(None, "{\"a\":\"aGk=\"}", "JSON", families::Family::JSON);
+        outer.children.push(layer(Some("/a"), "aGk=", "Base64", families::Family::BASE64));
+
+        let expected = format!("{}{}\n\n{}{}",
+                               get_header("JSON", &Palette::plain()),
+                               "{\n  \"a\": \"aGk=\"\n}",
+                               get_field_header("/a", "Base64", &Palette::plain()),
+                               "hi");
+        assert_eq!(expected, present_layer(&outer, &Palette::plain(), false));
+    }
+
+    #[test]
+    fn humanised_durations() {
+        assert_eq!("1 second", humanise_seconds(1));
+        assert_eq!("59 seconds", humanise_seconds(-59));
+        assert_eq!("5 minutes", humanise_seconds(300));
+        assert_eq!("3 hours", humanise_seconds(3 * 60 * 60 + 59));
+        assert_eq!("4 days", humanise_seconds(4 * 24 * 60 * 60));
+    }
+
+    #[test]
+    fn claim_times_are_annotated() {
+        let now = 1516239022;
+        assert_eq!("exp: 2018-01-18 01:30:22 UTC (expired 5 minutes ago)",
+                   describe_claim_time("exp", now, now + 300));
+        assert_eq!("exp: 2018-01-18 01:30:22 UTC (expires in 5 minutes)",
+                   describe_claim_time("exp", now, now - 300));
+        assert_eq!("iat: 2018-01-18 01:30:22 UTC (issued 10 seconds ago)",
+                   describe_claim_time("iat", now, now + 10));
+        assert_eq!("nbf: 2018-01-18 01:30:22 UTC (not valid for another 2 hours)",
+                   describe_claim_time("nbf", now, now - 2 * 60 * 60));
+    }
+
+    #[test]
+    fn jwt_header_and_claims_are_shown() {
+        let jwt = Jwt {
+            header: json!({"alg": "none"}),
+            claims: json!({"sub": "me", "exp": 1516239022}),
+            signature: "".into(),
+        };
+
+        let expected = format!("Header:\n\n{}\n\nClaims:\n\n{}\n\nTimes:\n\n{}\n\n{}",
+                               serde_json::to_string_pretty(&jwt.header).unwrap(),
+                               serde_json::to_string_pretty(&jwt.claims).unwrap(),
+                               "exp: 2018-01-18 01:30:22 UTC (expired 1 minute ago)",
+                               "WARNING: alg is \"none\" - this token is unsigned and anyone could have written it");
+
+        assert_eq!(expected, describe_jwt(&jwt, 1516239082, &Palette::plain()));
+    }
+
+    #[test]
+    fn jwt_signature_findings_are_shown() {
+        // {"alg":"HS256"}.{"sub":"me"}.c2ln
+        let jwt = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiJtZSJ9.c2ln";
+        let mut card = ReportCard::new(1.0, "JSON Web Token".into(), families::Family::JWT);
+        card.notes.push("HS256 signature verified with --secret".into());
+
+        let result = present(&Input::from(jwt), &Some(card), &Palette::plain(), false);
+        assert!(result.ends_with("\n\nSignature:\n\nHS256 signature verified with --secret"));
+    }
+
+    #[test]
+    fn known_errors_are_listed() {
+        let card = ReportCard::new_with_errors(1.0,
+                                               "JSON Web Token".into(),
+                                               families::Family::JWT,
+                                               vec![ParseError::new("HS256 signature not verified", 1, 38),
+                                                    ParseError::new("somewhere", 0, 0)]);
+
+        let result = present(&Input::from("not.a.jwt"), &Some(card), &Palette::plain(), false);
+        assert_eq!(format!("{}not.a.jwt\n\n\
+                            error: HS256 signature not verified\n \
+                            --> 1:38\n  \
+                            |\n\
+                            1 | not.a.jwt\n  \
+                            |                                      ^\n\n\
+                            error: somewhere", get_header("JSON Web Token", &Palette::plain())),
+                   result);
+    }
+
+    #[test]
+    fn errors_are_shown_in_context() {
+        let input = "{\n  \"a\": 1,\n  \"b\": 2,\n\t\"c\": 3,\n  \"d\": 4,\n}";
+        let errors = vec![ParseError::new("trailing comma", 6, 1), ParseError::new("tabbed", 4, 2)];
+
+        assert_eq!("error: trailing comma\n \
+                    --> 6:1\n  \
+                    |\n\
+                    4 | \t\"c\": 3,\n\
+                    5 |   \"d\": 4,\n\
+                    6 | }\n  \
+                    | ^\n\n\
+                    error: tabbed\n \
+                    --> 4:2\n  \
+                    |\n\
+                    2 |   \"a\": 1,\n\
+                    3 |   \"b\": 2,\n\
+                    4 | \t\"c\": 3,\n  \
+                    | \t^\n\
+                    5 |   \"d\": 4,\n\
+                    6 | }",
+                   annotate_errors(input, &errors, &Palette::plain()));
+    }
+
+    #[test]
+    fn errors_past_the_input_have_no_snippet() {
+        assert_eq!("error: lost", annotate_error("one line", &ParseError::new("lost", 3, 1), &Palette::plain()));
+    }
+
+    #[test]
+    fn self_describing_json_has_schema_header() {
+        let given_format = Some(ReportCard::new(1.0, "Self-describing JSON".into(), families::Family::SELF_DESCRIBING_JSON));
+        let sdj = r#"{"schema": "iglu:com.acme/my_event/jsonschema/1-0-2", "data": {"hello": "world"}}"#;
+
+        let expected = format!("{}{}",
+                               get_header("Self-describing JSON", &Palette::plain()),
+                               "Schema:  iglu:com.acme/my_event/jsonschema/1-0-2\n\
+                                Vendor:  com.acme\n\
+                                Name:    my_event\n\
+                                Format:  jsonschema\n\
+                                Version: 1-0-2\n\n\
+                                {\n  \"hello\": \"world\"\n}");
+
+        assert_eq!(expected, present(&Input::from(sdj), &given_format, &Palette::plain(), false));
+    }
+
+    #[test]
+    fn self_describing_json_contexts_are_unwrapped() {
+        let sdj = json!({
+            "schema": "iglu:com.snowplowanalytics.snowplow/contexts/jsonschema/1-0-0",
+            "data": [
+                {"schema": "iglu:com.acme/a/jsonschema/1-0-0", "data": {"a": 1}},
+                {"schema": "iglu:com.acme/b/jsonschema/2-0-0", "data": {"b": 2}}
+            ]
+        });
+        let (schema, data) = iglu::envelope(&sdj).expect("bad test config");
+
+        let described = describe_envelope(schema, data, &Palette::plain());
+        let blocks: Vec<&str> = described.split("\n\n").collect();
+
+        assert_eq!(5, blocks.len());
+        assert!(blocks[0].starts_with("Schema:  iglu:com.snowplowanalytics.snowplow/contexts/jsonschema/1-0-0"));
+        assert!(blocks[1].starts_with("Schema:  iglu:com.acme/a/jsonschema/1-0-0"));
+        assert_eq!("{\n  \"a\": 1\n}", blocks[2]);
+        assert!(blocks[3].starts_with("Schema:  iglu:com.acme/b/jsonschema/2-0-0"));
+        assert_eq!("{\n  \"b\": 2\n}", blocks[4]);
+    }
+
+    #[test]
+    fn bad_jwt_is_printed_as_is() {
+        let given_format = Some(ReportCard::new(1.0, "JSON Web Token".into(), families::Family::JWT));
+        let result = present(&Input::from("not.a.jwt"), &given_format, &Palette::plain(), false);
+        assert_eq!(format!("{}{}", get_header("JSON Web Token", &Palette::plain()), "not.a.jwt"), result);
+    }
+
+    #[test]
+    fn explanation_ranks_every_card() {
+        let mut base64 = ReportCard::new_with_factors("Base64".into(),
+                                                      families::Family::BASE64,
+                                                      vec![Factor::new("looks right", 0.6), Factor::new("too short", -0.1)]);
+        base64.strategy_id = "B64_ONLY".into();
+
+        let mut json = ReportCard::new_with_factors("JSON".into(),
+                                                    families::Family::JSON,
+                                                    vec![Factor::new("doesn't parse", 0.0)]);
+        json.strategy_id = "JSON_ONLY".into();
+        json.known_errors.push(ParseError::new("expected value", 1, 1));
+
+        let layer = Layer::new(None, "abcd".into(), vec![base64, json]);
+
+        let expected = "Scrutey's reasoning:\n\n\
+                        Rank  Sureness  Strategy   Name    Family\n\
+                        1     0.50      B64_ONLY   Base64  BASE64\n      \
+                        + 0.60 looks right\n      \
+                        - 0.10 too short\n\
+                        2     0.00      JSON_ONLY  JSON    JSON\n      \
+                        = 0.00 doesn't parse\n      \
+                        ! 1:1 expected value";
+
+        assert_eq!(expected, present_explanation(&layer, &Palette::plain()));
+    }
+
     // and if there are errors in the reportcard?
 }
\ No newline at end of file
@@ -6,6 +6,33 @@ pub struct ReportCard {
     pub type_friendly_name: String,
     pub family: families::Family,
     pub known_errors: Vec<ParseError>,
+    pub notes: Vec<String>,
+    pub factors: Vec<Factor>,
+    pub strategy_id: String,
+    // what a compressed input inflated to, so it only has to be inflated once
+    pub inflated: Option<Vec<u8>>,
+}
+
+// one reason a strategy is more or less sure, and how much it moved the score
+#[derive(Debug, PartialEq, Clone)]
+pub struct Factor {
+    pub reason: String,
+    pub weight: f32,
+}
+
+impl Factor {
+    pub fn new(reason: &str, weight: f32) -> Self {
+        Factor {
+            reason: String::from(reason),
+            weight,
+        }
+    }
+}
+
+// the sum of all weights, kept between 0 and 1 and rounded to two places
+pub fn score(factors: &[Factor]) -> f32 {
+    let total: f32 = factors.iter().map(|f| f.weight).sum();
+    (total.clamp(0.0, 1.0) * 100.0).round() / 100.0
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -16,13 +43,35 @@ pub struct ParseError {
 }
 
 impl ParseError {
-    fn new(message: &str, line: u32, col: u32) -> Self {
+    pub fn new(message: &str, line: u32, col: u32) -> Self {
         ParseError {
             message: String::from(message),
             line: line.into(),
             col: col.into()
         }
     }
+
+    // an error positioned at the first occurrence of needle in the input, or 0:0 if it isn't there
+    pub fn at(message: &str, input: &str, needle: &str) -> Self {
+        let (line, col) = position_of(input, needle);
+        ParseError::new(message, line, col)
+    }
+}
+
+// 1-based line and column of the first occurrence of needle, or 0:0 if it can't be found
+pub fn position_of(input: &str, needle: &str) -> (u32, u32) {
+    match input.find(needle) {
+        Some(offset) => position_at(input, offset),
+        None => (0, 0),
+    }
+}
+
+// 1-based line and column, in characters, of a byte offset into the input
+pub fn position_at(input: &str, offset: usize) -> (u32, u32) {
+    let before = &input[..offset.min(input.len())];
+    let line = before.matches('\n').count() + 1;
+    let col = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    (line as u32, col as u32)
 }
 
 impl ReportCard {
@@ -36,6 +85,10 @@ impl ReportCard {
             type_friendly_name: type_friendly_name,
             family: family,
             known_errors: known_errors,
+            notes: vec![],
+            factors: vec![],
+            strategy_id: String::new(),
+            inflated: None,
         }
     }
 
@@ -45,6 +98,26 @@ impl ReportCard {
             type_friendly_name: type_friendly_name,
             family: family,
             known_errors: vec![],
+            notes: vec![],
+            factors: vec![],
+            strategy_id: String::new(),
+            inflated: None,
+        }
+    }
+
+    pub fn new_with_factors(type_friendly_name: String,
+                            family: families::Family,
+                            factors: Vec<Factor>)
+                            -> Self {
+        ReportCard {
+            sureness: score(&factors),
+            type_friendly_name,
+            family,
+            known_errors: vec![],
+            notes: vec![],
+            factors,
+            strategy_id: String::new(),
+            inflated: None,
         }
     }
 }
@@ -63,9 +136,51 @@ mod tests {
                        type_friendly_name: "friend".into(),
                        family: families::Family::UNSTRUCTURED,
                        known_errors: vec![],
+                       notes: vec![],
+                       factors: vec![],
+                       strategy_id: String::new(),
+                       inflated: None,
+                   })
+    }
+
+    #[test]
+    fn new_with_factors() {
+        let factors = vec![Factor::new("good", 0.7), Factor::new("bad", -0.2)];
+        let sample_card = ReportCard::new_with_factors("friend".into(), families::Family::UNSTRUCTURED, factors.clone());
+        assert_eq!(sample_card,
+                   ReportCard {
+                       sureness: 0.5,
+                       type_friendly_name: "friend".into(),
+                       family: families::Family::UNSTRUCTURED,
+                       known_errors: vec![],
+                       notes: vec![],
+                       factors,
+                       strategy_id: String::new(),
+                       inflated: None,
                    })
     }
 
+    #[test]
+    fn scores_are_clamped_and_rounded() {
+        assert_eq!(1.0, score(&[Factor::new("a", 0.3), Factor::new("b", 0.2), Factor::new("c", 0.1), Factor::new("d", 0.1), Factor::new("e", 0.3)]));
+        assert_eq!(1.0, score(&[Factor::new("a", 1.5)]));
+        assert_eq!(0.0, score(&[Factor::new("a", 0.1), Factor::new("b", -0.5)]));
+        assert_eq!(0.0, score(&[]));
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!((1, 1), position_of("abc", "a"));
+        assert_eq!((2, 3), position_of("abc\nxyz", "z"));
+        assert_eq!((0, 0), position_of("abc", "q"));
+        assert_eq!((2, 2), position_at("é\nxyz", 4));
+    }
+
+    #[test]
+    fn error_at() {
+        assert_eq!(ParseError::new("here", 2, 1), ParseError::at("here", "{\n\"a\": 1}", "\"a\""));
+    }
+
     #[test]
     fn new_with_errors() {
         let sample_card = ReportCard::new_with_errors(1.0, "friend".into(), families::Family::UNSTRUCTURED, vec![ParseError::new("message", 1, 2)]);
@@ -79,6 +194,10 @@ mod tests {
                            line: 1,
                            col: 2
                        }],
+                       notes: vec![],
+                       factors: vec![],
+                       strategy_id: String::new(),
+                       inflated: None,
                    })
     }
 
@@ -2,34 +2,93 @@ use checker::{Checker, NaiveChecker};
 use ReportCard;
 use std::cmp::Ordering::Equal;
 use presenter;
+use decoder;
+use layer::Layer;
+use options::{Options, OutputFormat};
+use json_presenter;
+use families::Family;
+use input::Input;
 
-// main app, with command line args collected 
+// anything less sure than this isn't worth peeling open
+const SURE_ENOUGH: f32 = 0.5;
 
-pub fn scrutinize<T: Checker>(input: Result<String,String>, checker: &T) -> Result<String,String> {
+// main app, with command line args collected
 
-    let raw_input = try!(input);
-    let string_to_test = raw_input.trim();
+pub fn scrutinize<T: Checker>(input: Result<Vec<u8>,String>, checker: &T, options: &Options) -> Result<String,String> {
 
-    // run all the checks
+    let raw_input = Input::from(input?);
+    let to_test = raw_input.trimmed();
 
-    let mut report_cards = checker.check(&string_to_test);
+    // run all the checks, then keep checking whatever they decode to
 
-    if report_cards.len() < 1 {
-        return Err("internal error; checker returned no results".into());
+    let layers = peel(None, to_test, checker, options, &mut vec![])?;
+
+    // the JSON document already carries every report card, so --explain has nothing to add
+    if options.output == OutputFormat::Json {
+        return Ok(json_presenter::present(&layers));
     }
 
-    report_cards.sort_by(|b,a| a.sureness.partial_cmp(&b.sureness).unwrap_or(Equal));
+    // run the layers through the presenter
+    let mut formatted_result = presenter::present_layer(&layers, &options.palette, options.hexdump);
 
-    // pick the top result
-    let top = Some(report_cards[0].clone());
+    if options.explain {
+        formatted_result.push_str("\n\n");
+        formatted_result.push_str(&presenter::present_explanation(&layers, &options.palette));
+    }
 
-    // run the reportcard through the presenter
-    let formatted_result = presenter::present(&string_to_test, &top);
-      
     // return the result
     Ok(formatted_result)
 }
 
+fn recognised(layer: &Layer) -> bool {
+    let top = layer.top();
+    top.family != Family::UNSTRUCTURED && top.sureness >= SURE_ENOUGH
+}
+
+// check the input, then recurse into every payload of the best result until nothing more
+// can be peeled off, the depth limit is hit, or a payload repeats one of its ancestors
+fn peel<T: Checker>(path: Option<String>,
+                    input: Input,
+                    checker: &T,
+                    options: &Options,
+                    ancestors: &mut Vec<Input>)
+                    -> Result<Layer, String> {
+
+    let mut report_cards = checker.check(&input);
+
+    if report_cards.is_empty() {
+        return Err("internal error; checker returned no results".into());
+    }
+
+    report_cards.sort_by(|b,a| a.sureness.partial_cmp(&b.sureness).unwrap_or(Equal));
+
+    let mut layer = Layer::new(path, input, report_cards);
+
+    if ancestors.len() >= options.max_depth || !recognised(&layer) {
+        return Ok(layer);
+    }
+
+    ancestors.push(layer.input.clone());
+
+    for payload in decoder::payloads(&layer.input, layer.top()) {
+        let content = payload.content.trimmed();
+
+        if content.is_empty() || ancestors.iter().any(|a| *a == content) {
+            continue;
+        }
+
+        let child = peel(payload.path, content, checker, options, ancestors)?;
+
+        if recognised(&child) {
+            layer.children.push(child);
+        }
+    }
+
+    ancestors.pop();
+
+    Ok(layer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,35 +99,35 @@ mod tests {
 
     struct MockChecker {}
     impl Checker for MockChecker {
-        fn check(&self, input: &str) -> Vec<ReportCard> {
+        fn check(&self, input: &Input) -> Vec<ReportCard> {
             vec![
                 ReportCard::new(0.0, "base64".into(), families::Family::BASE64), // base64
                 ReportCard::new(1.0, "json".into(), families::Family::JSON), // json
             ]
         }
-    }   
+    }
     impl MockChecker {
         pub fn new() -> Self { MockChecker {} }
-    } 
+    }
 
     #[test]
     fn input_errors_rejected() {
         let chk = MockChecker::new();
-        assert_eq!(Err("error message".to_owned()), scrutinize(Err("error message".to_owned()), &chk));
+        assert_eq!(Err("error message".to_owned()), scrutinize(Err("error message".to_owned()), &chk, &Options::default()));
     }
 
     #[test]
     fn return_error_if_no_results() {
         struct EmptyChecker {}
         impl Checker for EmptyChecker {
-            fn check(&self, input: &str) -> Vec<ReportCard> {
+            fn check(&self, input: &Input) -> Vec<ReportCard> {
                 vec![]
             }
         }
 
         let bad_checker = EmptyChecker {};
 
-        assert_eq!(Err("internal error; checker returned no results".to_owned()), scrutinize(Ok("any data".to_owned()), &bad_checker))
+        assert_eq!(Err("internal error; checker returned no results".to_owned()), scrutinize(Ok("any data".into()), &bad_checker, &Options::default()))
     }
 
     #[test]
@@ -76,11 +135,87 @@ mod tests {
         let chk = MockChecker::new();
         let json = "{\"hello\": \"world\"}";
         let sample = Some(ReportCard::new(1.0, "json".into(), families::Family::JSON));
-        
-        let presented = presenter::present(json, &sample);
-        let result = scrutinize(Ok(json.into()), &chk).expect("bad test config");
-                      
-        assert_eq!(presented, result);    
-    }
-    
-}
\ No newline at end of file
+
+        let presented = presenter::present(&json.into(), &sample, &Options::default().palette, false);
+        let result = scrutinize(Ok(json.into()), &chk, &Options::new(0)).expect("bad test config");
+
+        assert_eq!(presented, result);
+    }
+
+    #[test]
+    fn explanation_is_appended() {
+        let chk = MockChecker::new();
+        let json = "{\"hello\": 1}";
+        let mut options = Options::default();
+        options.explain = true;
+
+        let result = scrutinize(Ok(json.into()), &chk, &options).expect("bad test config");
+        let layer = peel(None, json.into(), &chk, &options, &mut vec![]).expect("bad test config");
+
+        assert_eq!(format!("{}\n\n{}", presenter::present_layer(&layer, &options.palette, options.hexdump), presenter::present_explanation(&layer, &options.palette)), result);
+    }
+
+    #[test]
+    fn json_output() {
+        let chk = MockChecker::new();
+        let json = "{\"hello\": 1}";
+        let mut options = Options::default();
+        options.output = OutputFormat::Json;
+        options.explain = true;
+
+        let result = scrutinize(Ok(json.into()), &chk, &options).expect("bad test config");
+        let layer = peel(None, json.into(), &chk, &options, &mut vec![]).expect("bad test config");
+
+        assert_eq!(json_presenter::present(&layer), result);
+    }
+
+    #[test]
+    fn binary_input_is_a_result() {
+        let result = scrutinize(Ok(vec![0x1f, 0x8b, 0x08, 0x00]), &NaiveChecker::new(), &Options::default())
+            .expect("binary shouldn't be an error");
+
+        assert!(result.starts_with("Scrutey thinks this is binary data:"));
+    }
+
+    #[test]
+    fn base64_json_is_peeled() {
+        // {"inner": "aGVsbG8gd29ybGQ="}
+        let layer = peel(None, "eyJpbm5lciI6ICJhR1ZzYkc4Z2QyOXliR1E9In0=".into(), &NaiveChecker::new(), &Options::default(), &mut vec![])
+            .expect("bad test config");
+
+        assert_eq!(layer.top().family, families::Family::BASE64);
+
+        let json = layer.decoded().expect("base64 should decode to json");
+        assert_eq!(json.top().family, families::Family::JSON);
+
+        let fields = json.fields();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].path, Some("/inner".to_owned()));
+        assert_eq!(fields[0].top().family, families::Family::BASE64);
+    }
+
+    #[test]
+    fn max_depth_is_respected() {
+        let layer = peel(None, "eyJpbm5lciI6ICJhR1ZzYkc4Z2QyOXliR1E9In0=".into(), &NaiveChecker::new(), &Options::new(1), &mut vec![])
+            .expect("bad test config");
+
+        let json = layer.decoded().expect("base64 should decode to json");
+        assert_eq!(json.children, vec![]);
+    }
+
+    #[test]
+    fn loops_are_not_followed() {
+        struct LoopChecker {}
+        impl Checker for LoopChecker {
+            fn check(&self, _: &Input) -> Vec<ReportCard> {
+                vec![ReportCard::new(1.0, "json".into(), families::Family::JSON)]
+            }
+        }
+
+        // the only leaf is identical to its parent's input once decoded
+        let layer = peel(None, "[\"[\\\"x\\\"]\"]".into(), &LoopChecker {}, &Options::new(100), &mut vec!["[\"x\"]".into()])
+            .expect("bad test config");
+
+        assert_eq!(layer.children, vec![]);
+    }
+}
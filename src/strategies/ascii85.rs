@@ -0,0 +1,78 @@
+// Adobe's Ascii85, as found in PostScript and PDF streams
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder;
+
+const A85_ID_NAME: &str = "A85";
+const FRIENDLY_NAME: &str = "Ascii85";
+
+pub struct Ascii85Strategy {}
+
+impl StrategyType for Ascii85Strategy {
+    fn id(&self) -> String {
+        String::from(A85_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::ASCII85
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text.trim(),
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        if !input.starts_with("<~") || !input.ends_with("~>") {
+            let rejected = vec![Factor::new("not wrapped in <~ and ~>", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let factors = vec![Factor::new("wrapped in <~ and ~>", 0.4),
+                           strategy::length_factor(input.len(), "ascii85"),
+                           strategy::decoded_factor(decoder::decode_ascii85(input))];
+
+        ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(Ascii85Strategy {}.id(), A85_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, Ascii85Strategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Ascii85Strategy {}.family(), families::Family::ASCII85);
+    }
+
+    #[test]
+    fn parse_good() {
+        let report_card = Ascii85Strategy {}.parse(&"<~87cURD]i,\"Ebo80~>".into());
+        assert_eq!(report_card.sureness, 0.9);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(Ascii85Strategy {}.parse(&"87cURD]i,\"Ebo80".into()).sureness, 0.0);
+        assert_eq!(Ascii85Strategy {}.parse(&"<~ {not} ascii85 ~>".into()).sureness, 0.0);
+    }
+}
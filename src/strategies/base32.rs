@@ -0,0 +1,102 @@
+// RFC 4648 base32, as used by TOTP secrets, onion addresses and the like
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder::{self, Base32Variant};
+
+const B32_ID_NAME: &str = "B32";
+const FRIENDLY_NAME: &str = "Base32";
+
+pub struct Base32Strategy {}
+
+fn variant_factor(variant: Base32Variant) -> Factor {
+    match variant {
+        Base32Variant::Standard => Factor::new("made of base32 characters in groups of eight with valid padding", 0.3),
+        Base32Variant::Hex => Factor::new("made of base32hex characters in groups of eight with valid padding", 0.3),
+        Base32Variant::Unpadded => Factor::new("made of base32 characters, without the = padding", 0.2),
+        Base32Variant::HexUnpadded => Factor::new("made of base32hex characters, without the = padding", 0.2),
+    }
+}
+
+impl StrategyType for Base32Strategy {
+    fn id(&self) -> String {
+        String::from(B32_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::BASE32
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let variant = match decoder::base32_variant(input) {
+            Some(variant) => variant,
+            None => {
+                let rejected = vec![Factor::new("not made of upper case base32 characters", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let mut factors = vec![variant_factor(variant), strategy::length_factor(input.trim().len(), "base32")];
+
+        if input.trim().ends_with('=') {
+            factors.push(Factor::new("ends in = padding", 0.1));
+        }
+
+        factors.push(strategy::decoded_factor(decoder::decode_base32(input)));
+
+        ReportCard::new_with_factors(variant.name().into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(Base32Strategy {}.id(), B32_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, Base32Strategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Base32Strategy {}.family(), families::Family::BASE32);
+    }
+
+    #[test]
+    fn parse_good() {
+        let report_card = Base32Strategy {}.parse(&"NBSWY3DPEB3W64TMMQ======".into());
+        assert_eq!(report_card.sureness, 0.9);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_hex_alphabet() {
+        let report_card = Base32Strategy {}.parse(&"D1IMOR3F41RMUSJCCG".into());
+        assert_eq!(report_card.sureness, 0.7);
+        assert_eq!(report_card.type_friendly_name, "Base32hex (unpadded)");
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        let report_card = Base32Strategy {}.parse(&"potato".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+}
@@ -0,0 +1,121 @@
+// bitcoin-style base58, which is only really convincing with a base58check checksum
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder;
+
+const B58_ID_NAME: &str = "B58";
+const FRIENDLY_NAME: &str = "Base58";
+const CHECKED_NAME: &str = "Base58Check";
+
+pub struct Base58Strategy {}
+
+// addresses and keys mix all three; words don't
+fn distribution_factor(input: &str) -> Factor {
+    let classes = [input.chars().any(|c| c.is_ascii_uppercase()),
+                   input.chars().any(|c| c.is_ascii_lowercase()),
+                   input.chars().any(|c| c.is_ascii_digit())];
+
+    match classes.iter().filter(|c| **c).count() {
+        3 => Factor::new("mixes upper case, lower case and digits", 0.1),
+        2 => Factor::new("uses two of upper case, lower case and digits", 0.0),
+        _ => Factor::new("only uses one kind of character", -0.2),
+    }
+}
+
+impl StrategyType for Base58Strategy {
+    fn id(&self) -> String {
+        String::from(B58_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::BASE58
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text.trim(),
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let decoded = match decoder::decode(self.family(), input) {
+            Some(decoded) => decoded,
+            None => {
+                let rejected = vec![Factor::new("not made of base58 characters", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let checked = decoder::decode_base58check(input);
+
+        // almost every word is valid base58, and decodes to noise
+        if checked.is_none() && !strategy::makes_sense(&decoded) && input.len() < strategy::UNLIKELY_PROSE_LENGTH {
+            let rejected = vec![Factor::new("short, and decodes to nothing that makes sense", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![Factor::new("made of base58 characters", 0.2),
+                               strategy::length_factor(input.len(), "base58"),
+                               distribution_factor(input)];
+
+        let name = if checked.is_some() {
+            factors.push(Factor::new("ends in a valid base58check checksum", 0.4));
+            CHECKED_NAME
+        } else {
+            FRIENDLY_NAME
+        };
+
+        factors.push(strategy::decoded_factor(Some(decoded)));
+
+        ReportCard::new_with_factors(name.into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(Base58Strategy {}.id(), B58_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, Base58Strategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Base58Strategy {}.family(), families::Family::BASE58);
+    }
+
+    #[test]
+    fn parse_checked() {
+        let report_card = Base58Strategy {}.parse(&"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".into());
+        assert_eq!(report_card.sureness, 0.8);
+        assert_eq!(report_card.type_friendly_name, CHECKED_NAME);
+    }
+
+    #[test]
+    fn parse_unchecked() {
+        let report_card = Base58Strategy {}.parse(&"StV1DL6CwTryKyV".into());
+        assert_eq!(report_card.sureness, 0.7);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(Base58Strategy {}.parse(&"potato".into()).sureness, 0.0);
+        assert_eq!(Base58Strategy {}.parse(&"hello world".into()).sureness, 0.0);
+        assert_eq!(Base58Strategy {}.parse(&"face".into()).sureness, 0.0);
+        assert_eq!(Base58Strategy {}.parse(&"Kubernetes".into()).sureness, 0.0);
+    }
+}
@@ -1,13 +1,49 @@
 use families;
-use strategies::strategy::StrategyType;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
 use ReportCard;
-use regex::Regex;
+use report_card::Factor;
+use decoder::{self, Base64Variant};
 
 const B64_ID_NAME: &'static str = "B64_ONLY";
 const FRIENDLY_NAME: &'static str = "Base64";
 
 pub struct Base64Strategy {}
 
+// without padding there's less to tell base64 apart from any other run of letters
+fn variant_factor(variant: Base64Variant) -> Factor {
+    match variant {
+        Base64Variant::Standard => Factor::new("made of base64 characters in groups of four with valid padding", 0.3),
+        Base64Variant::UrlSafe => Factor::new("made of URL-safe base64 characters in groups of four with valid padding", 0.3),
+        Base64Variant::Unpadded => Factor::new("made of base64 characters, without the = padding", 0.2),
+        Base64Variant::UrlSafeUnpadded => Factor::new("made of URL-safe base64 characters, without the = padding", 0.2),
+        Base64Variant::Mime => Factor::new("base64 wrapped into lines of the same length, as MIME does", 0.3),
+    }
+}
+
+// real base64 mixes upper case, lower case and digits; words and hex mostly don't
+fn distribution_factor(input: &str) -> Factor {
+    let body = input.trim_end_matches('=');
+    let classes = [body.chars().any(|c| c.is_ascii_uppercase()),
+                   body.chars().any(|c| c.is_ascii_lowercase()),
+                   body.chars().any(|c| c.is_ascii_digit() || "+/-_".contains(c))];
+
+    match classes.iter().filter(|c| **c).count() {
+        3 => Factor::new("mixes upper case, lower case and digits", 0.1),
+        2 => Factor::new("uses two of upper case, lower case and digits", 0.0),
+        _ => Factor::new("only uses one kind of character", -0.2),
+    }
+}
+
+// hashes and other hex are valid base64 surprisingly often, but hex is the likelier reading
+fn hex_factor(input: &str) -> Option<Factor> {
+    if input.trim_end_matches('=').chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(Factor::new("only uses hex digits, which hex explains better", -0.2))
+    } else {
+        None
+    }
+}
+
 impl StrategyType for Base64Strategy {
     fn id(&self) -> String {
         String::from(B64_ID_NAME)
@@ -21,14 +57,40 @@ impl StrategyType for Base64Strategy {
         families::Family::BASE64
     }
 
-    fn parse(&self, input: &str) -> ReportCard {
-        let re = Regex::new(r"^([A-Za-z0-9+/]{4})*([A-Za-z0-9+/]{4}|[A-Za-z0-9+/]{3}=|[A-Za-z0-9+/]{2}==)$").unwrap();
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let variant = match decoder::base64_variant(input) {
+            Some(variant) => variant,
+            None => {
+                let rejected = vec![Factor::new("not made of base64 characters in groups of four with valid padding", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let decoded = decoder::decode_base64(input);
+
+        // without padding, or with - and _, a word or a UUID fits just as well as base64 does
+        let unconvincing = variant != Base64Variant::Standard && variant != Base64Variant::Mime;
+        if unconvincing && !decoded.as_ref().map(|d| strategy::makes_sense(d)).unwrap_or(false) {
+            let rejected = vec![Factor::new("unpadded or URL-safe, and decodes to nothing that makes sense", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![variant_factor(variant), strategy::length_factor(input.len(), "base64")];
 
-        if re.is_match(input) {
-            ReportCard::new(1.0, FRIENDLY_NAME.into(), self.family())
-        } else {
-            ReportCard::new(0.0, FRIENDLY_NAME.into(), self.family())
+        if input.ends_with('=') {
+            factors.push(Factor::new("ends in = padding", 0.1));
         }
+
+        factors.push(distribution_factor(input));
+        factors.extend(hex_factor(input));
+        factors.push(strategy::decoded_factor(decoded));
+
+        ReportCard::new_with_factors(variant.name().into(), self.family(), factors)
     }
 }
 
@@ -56,7 +118,7 @@ mod tests {
     #[test]
     fn parse_good() {
         let good_base64 = "aGVsbG8gd29ybGQ=";
-        let report_card = Base64Strategy{}.parse(good_base64);
+        let report_card = Base64Strategy{}.parse(&good_base64.into());
         assert_eq!(report_card.family, Base64Strategy{}.family());
         assert_eq!(report_card.known_errors, vec![]);
         assert_eq!(report_card.sureness, 1.0);
@@ -66,11 +128,62 @@ mod tests {
     #[test]
     fn parse_nonsense() {
         let bad_b64 = "potato";
-        let report_card =  Base64Strategy{}.parse(bad_b64);
+        let report_card =  Base64Strategy{}.parse(&bad_b64.into());
         assert_eq!(report_card.family, Base64Strategy{}.family());
         assert_eq!(report_card.known_errors, vec![]);
         assert_eq!(report_card.sureness, 0.0);
         assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+
+        for word in ["HelloWorld", "Kubernetes", "550e8400-e29b-41d4-a716-446655440000"] {
+            let report_card = Base64Strategy{}.parse(&word.into());
+            assert_eq!(report_card.sureness, 0.0);
+            assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+        }
     }
 
+    #[test]
+    fn parse_short_word() {
+        let report_card = Base64Strategy{}.parse(&"abcd".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert!(report_card.factors.contains(&Factor::new("only uses one kind of character", -0.2)));
+    }
+
+    #[test]
+    fn parse_binary() {
+        // random bytes
+        let report_card = Base64Strategy{}.parse(&"q83v3q2+7+8Afy9T".into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert!(report_card.factors.contains(&Factor::new("decodes to unrecognised binary", -0.1)));
+    }
+
+    #[test]
+    fn parse_hex() {
+        let report_card = Base64Strategy{}.parse(&"deadbeefcafebabe".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert!(report_card.factors.contains(&Factor::new("only uses hex digits, which hex explains better", -0.2)));
+    }
+
+    #[test]
+    fn parse_magic_number() {
+        // a PNG header
+        let report_card = Base64Strategy{}.parse(&"iVBORw0KGgoAAAANSUhEUg==".into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert!(report_card.factors.contains(&Factor::new("decodes to a PNG image", 0.3)));
+    }
+
+    #[test]
+    fn parse_variants() {
+        let url_safe = Base64Strategy{}.parse(&"eyJhIjoiPz8_In0".into());
+        assert_eq!(url_safe.type_friendly_name, "Base64 (URL-safe, unpadded)");
+        assert_eq!(url_safe.sureness, 0.7);
+
+        let unpadded = Base64Strategy{}.parse(&"aGVsbG8gd29ybGQ".into());
+        assert_eq!(unpadded.type_friendly_name, "Base64 (unpadded)");
+        assert_eq!(unpadded.sureness, 0.7);
+
+        let mime = "aGVsbG8gd29ybGQgaGVsbG8gd29ybGQg\r\naGVsbG8gd29ybGQ=";
+        let mime = Base64Strategy{}.parse(&mime.into());
+        assert_eq!(mime.type_friendly_name, "Base64 (MIME)");
+        assert_eq!(mime.sureness, 1.0);
+    }
 }
\ No newline at end of file
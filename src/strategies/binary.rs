@@ -0,0 +1,77 @@
+// bytes that aren't text, so nothing that reads text can make sense of them
+
+use families;
+use strategies::strategy::StrategyType;
+use ReportCard;
+use report_card::Factor;
+use input::Input;
+
+const BINARY_ID_NAME: &str = "BINARY";
+const FRIENDLY_NAME: &str = "binary data";
+
+pub struct BinaryStrategy {}
+
+impl StrategyType for BinaryStrategy {
+    fn id(&self) -> String {
+        String::from(BINARY_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::BINARY
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let offset = match input.utf8_error_offset() {
+            Some(offset) => offset,
+            None => {
+                let rejected = vec![Factor::new("is UTF-8 text", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let factors = vec![Factor::new("isn't UTF-8 text", 0.5)];
+        let mut card = ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors);
+
+        card.notes.push(format!("{} bytes, the first that isn't UTF-8 is at offset {}", input.bytes().len(), offset));
+        card
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use families;
+
+    #[test]
+    fn id() {
+        assert_eq!(BINARY_ID_NAME, BinaryStrategy {}.id());
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, BinaryStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(families::Family::BINARY, BinaryStrategy {}.family());
+    }
+
+    #[test]
+    fn parse_binary() {
+        let report_card = BinaryStrategy {}.parse(&vec![0x1f, 0x8b, 0x08, 0x00].into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.notes, vec!["4 bytes, the first that isn't UTF-8 is at offset 1".to_owned()]);
+    }
+
+    #[test]
+    fn parse_text() {
+        let report_card = BinaryStrategy {}.parse(&"hello world".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.notes, Vec::<String>::new());
+    }
+}
@@ -0,0 +1,150 @@
+// compressed streams, which are only believed once they actually inflate
+
+use families::Family;
+use strategies::strategy::StrategyType;
+use ReportCard;
+use report_card::Factor;
+use input::Input;
+use decompress;
+use magic;
+use std::str;
+
+const BINARY_ID_NAME: &str = "BINARY";
+
+pub struct CompressedStrategy {
+    family: Family,
+}
+
+impl CompressedStrategy {
+    pub fn new(family: Family) -> Self {
+        CompressedStrategy { family }
+    }
+
+    fn algorithm(&self) -> &'static str {
+        decompress::algorithm(self.family).unwrap_or("unknown")
+    }
+}
+
+// what the inflated bytes turn out to be, if anything obvious
+fn inflated_factor(inflated: &[u8]) -> Factor {
+    if let Some(name) = magic::identify(inflated) {
+        Factor::new(&format!("inflates to a {}", name), 0.1)
+    } else if str::from_utf8(inflated).is_ok() {
+        Factor::new("inflates to text", 0.1)
+    } else {
+        Factor::new("inflates to unrecognised binary", 0.0)
+    }
+}
+
+fn ratio_factor(ratio: f64) -> Factor {
+    if ratio >= 1.0 {
+        Factor::new(&format!("inflates to {:.1} times its size", ratio), 0.1)
+    } else {
+        Factor::new("inflates to less than its own size, as very short inputs do", 0.0)
+    }
+}
+
+impl StrategyType for CompressedStrategy {
+    fn id(&self) -> String {
+        format!("COMPRESSED_{:?}", self.family)
+    }
+
+    // formats with a magic number are a more specific take on that file type
+    fn child_of_id(&self) -> Option<String> {
+        if self.family.is_file_type() {
+            Some(format!("MAGIC_{:?}", self.family))
+        } else {
+            Some(String::from(BINARY_ID_NAME))
+        }
+    }
+
+    fn family(&self) -> Family {
+        self.family
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let name = format!("{} compressed", self.algorithm());
+        let bytes = input.bytes();
+
+        let inflated = match decompress::inflate(self.family, bytes, decompress::LIMIT) {
+            Ok(ref inflated) if inflated.is_empty() => {
+                let rejected = vec![Factor::new("inflates to nothing", 0.0)];
+                return ReportCard::new_with_factors(name, self.family, rejected);
+            }
+            Ok(inflated) => inflated,
+            Err(e) => {
+                let rejected = vec![Factor::new(&format!("doesn't inflate with {} - {}", self.algorithm(), e), 0.0)];
+                return ReportCard::new_with_factors(name, self.family, rejected);
+            }
+        };
+
+        // raw deflate has no header or checksum, so plenty of random bytes inflate to something
+        let mut factors = match self.family {
+            Family::DEFLATE => vec![Factor::new("inflates as raw deflate, which has no header or checksum", 0.2)],
+            _ => vec![Factor::new(&format!("inflates with {}, checksums and all", self.algorithm()), 0.7)],
+        };
+
+        factors.push(match input.text() {
+            Some(_) => Factor::new("is UTF-8 text", -0.2),
+            None => Factor::new("isn't UTF-8 text", 0.1),
+        });
+
+        let ratio = decompress::ratio(bytes.len(), inflated.len());
+        factors.push(inflated_factor(&inflated));
+        factors.push(ratio_factor(ratio));
+
+        let mut card = ReportCard::new_with_factors(name, self.family, factors);
+        card.notes.push(format!("{} bytes inflate with {} to {} bytes, a compression ratio of {:.2}",
+                                bytes.len(), self.algorithm(), inflated.len(), ratio));
+        card.inflated = Some(inflated);
+        card
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZLIB: &[u8] = b"x\x9c\xcbH\xcd\xc9\xc9W\xc8\xc0G\x02\x00\xebU\x0d\x19";
+
+    #[test]
+    fn id() {
+        assert_eq!("COMPRESSED_ZLIB", CompressedStrategy::new(Family::ZLIB).id());
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(Some("MAGIC_GZIP".to_owned()), CompressedStrategy::new(Family::GZIP).child_of_id());
+        assert_eq!(Some(BINARY_ID_NAME.to_owned()), CompressedStrategy::new(Family::ZLIB).child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Family::ZSTD, CompressedStrategy::new(Family::ZSTD).family());
+    }
+
+    #[test]
+    fn parse_zlib() {
+        let report_card = CompressedStrategy::new(Family::ZLIB).parse(&ZLIB.into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, "zlib compressed");
+        assert_eq!(report_card.notes, vec!["17 bytes inflate with zlib to 35 bytes, a compression ratio of 2.06".to_owned()]);
+        assert_eq!(report_card.inflated, Some(b"hello hello hello hello hello hello".to_vec()));
+    }
+
+    #[test]
+    fn raw_deflate_needs_more_convincing() {
+        let report_card = CompressedStrategy::new(Family::DEFLATE).parse(&ZLIB[2..].into());
+        assert_eq!(report_card.sureness, 0.5);
+    }
+
+    #[test]
+    fn parse_not_compressed() {
+        let report_card = CompressedStrategy::new(Family::GZIP).parse(&ZLIB.into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.notes, Vec::<String>::new());
+
+        let report_card = CompressedStrategy::new(Family::ZLIB).parse(&"hello".into());
+        assert_eq!(report_card.sureness, 0.0);
+    }
+}
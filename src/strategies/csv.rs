@@ -0,0 +1,143 @@
+// comma, tab, semicolon or pipe separated values, as exported from spreadsheets and databases
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use delimited::{self, ColumnType, Table};
+
+const CSV_ID_NAME: &str = "CSV";
+const FRIENDLY_NAME: &str = "CSV";
+
+pub struct CsvStrategy {}
+
+pub fn friendly_name(delimiter: char) -> &'static str {
+    match delimiter {
+        '\t' => "TSV",
+        ';' => "CSV (semicolon separated)",
+        '|' => "CSV (pipe separated)",
+        _ => FRIENDLY_NAME,
+    }
+}
+
+// "well, yes, and no" splits into columns too, but csv rarely puts a space after every comma and nothing else
+fn reads_like_prose(table: &Table) -> bool {
+    let trailing: Vec<&String> = table.rows.iter().flat_map(|r| r.fields.iter().skip(1)).collect();
+    let spaced = trailing.iter().filter(|f| f.starts_with(' ')).count();
+
+    table.delimiter == ',' && spaced * 2 > trailing.len()
+        && table.column_types().iter().all(|t| *t == ColumnType::Text)
+}
+
+impl StrategyType for CsvStrategy {
+    fn id(&self) -> String {
+        String::from(CSV_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::CSV
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let table = match delimited::sniff(input) {
+            Some(table) => table,
+            None => {
+                let rejected = vec![Factor::new("doesn't split into columns over more than one line", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let rows = table.rows.len() + table.header.iter().count();
+        let ragged = table.ragged().len();
+
+        let mut factors = vec![Factor::new(&format!("{} rows split into {} columns", rows, table.columns), 0.3)];
+
+        factors.push(if ragged == 0 {
+            Factor::new("every row has the same number of columns", 0.4)
+        } else if ragged * 5 <= rows {
+            Factor::new("most rows have the same number of columns", 0.1)
+        } else {
+            Factor::new("rows have differing numbers of columns", -0.3)
+        });
+
+        if table.header.is_some() {
+            factors.push(Factor::new("has a header row", 0.1));
+        }
+
+        if rows > 2 {
+            factors.push(Factor::new("more than two rows", 0.1));
+        }
+
+        if table.column_types().iter().any(|t| *t != ColumnType::Text && *t != ColumnType::Empty) {
+            factors.push(Factor::new("has columns of numbers, dates or booleans", 0.1));
+        }
+
+        if reads_like_prose(&table) {
+            factors.push(Factor::new("words follow each comma, as in prose", -0.8));
+        }
+
+        let mut card = ReportCard::new_with_factors(friendly_name(table.delimiter).into(), self.family(), factors);
+        card.known_errors.extend(table.errors());
+        card
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(CsvStrategy {}.id(), CSV_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, CsvStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(CsvStrategy {}.family(), families::Family::CSV);
+    }
+
+    #[test]
+    fn parse_csv() {
+        let report_card = CsvStrategy {}.parse(&"id,name,joined\n1,alice,2020-01-01\n2,bob,2021-06-30\n".into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_tsv() {
+        let report_card = CsvStrategy {}.parse(&"a\tb\nc\td".into());
+        assert_eq!(report_card.sureness, 0.8);
+        assert_eq!(report_card.type_friendly_name, "TSV");
+    }
+
+    #[test]
+    fn parse_ragged() {
+        let report_card = CsvStrategy {}.parse(&"a;b;c\n1;2;3\n4;5;6\n7;8;9\n10;11\n".into());
+        assert_eq!(report_card.sureness, 0.7);
+        assert_eq!(report_card.type_friendly_name, "CSV (semicolon separated)");
+        assert_eq!(report_card.known_errors.len(), 1);
+        assert_eq!(report_card.known_errors[0].line, 5);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(CsvStrategy {}.parse(&"hello, world".into()).sureness, 0.0);
+        assert_eq!(CsvStrategy {}.parse(&"Well, yes\nand, no".into()).sureness, 0.0);
+        assert_eq!(CsvStrategy {}.parse(&"Dear Sam, thanks for the cake\nSee you soon, Alex".into()).sureness, 0.0);
+    }
+}
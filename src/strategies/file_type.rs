@@ -0,0 +1,117 @@
+// binary file types recognised by their magic numbers, one strategy per family
+
+use families::Family;
+use strategies::strategy::StrategyType;
+use ReportCard;
+use report_card::Factor;
+use input::Input;
+use magic;
+
+const PARENT_ID_NAME: &str = "BINARY";
+
+pub struct FileTypeStrategy {
+    family: Family,
+}
+
+impl FileTypeStrategy {
+    pub fn new(family: Family) -> Self {
+        FileTypeStrategy { family }
+    }
+}
+
+impl StrategyType for FileTypeStrategy {
+    fn id(&self) -> String {
+        format!("MAGIC_{:?}", self.family)
+    }
+
+    // a more specific kind of binary data
+    fn child_of_id(&self) -> Option<String> {
+        Some(String::from(PARENT_ID_NAME))
+    }
+
+    fn family(&self) -> Family {
+        self.family
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let name = magic::name(self.family);
+        let bytes = input.bytes();
+
+        let length = match magic::locate(bytes) {
+            Some((_, length, _)) if magic::family(bytes) == Some(self.family) => length,
+            _ => {
+                let rejected = vec![Factor::new(&format!("doesn't start with the {} signature", name), 0.0)];
+                return ReportCard::new_with_factors(name.into(), self.family, rejected);
+            }
+        };
+
+        // two or three bytes turn up by chance far more often than four or more
+        let mut factors = if length >= 4 {
+            vec![Factor::new(&format!("starts with the {} signature", name), 0.7)]
+        } else {
+            vec![Factor::new(&format!("starts with the short {} signature", name), 0.5)]
+        };
+
+        factors.push(match input.text() {
+            Some(_) => Factor::new("is UTF-8 text", -0.2),
+            None => Factor::new("isn't UTF-8 text", 0.1),
+        });
+
+        let details = magic::details(self.family, bytes);
+
+        factors.push(match details {
+            Some(_) => Factor::new("the header makes sense", 0.2),
+            None => Factor::new("the header doesn't make sense", -0.3),
+        });
+
+        let mut card = ReportCard::new_with_factors(name.into(), self.family, factors);
+        card.notes = details.unwrap_or_default();
+        card
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x01\x00\x00\x00\x01\x08\x00\x00\x00\x00";
+
+    #[test]
+    fn id() {
+        assert_eq!("MAGIC_PNG", FileTypeStrategy::new(Family::PNG).id());
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(Some(PARENT_ID_NAME.to_owned()), FileTypeStrategy::new(Family::PNG).child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Family::GZIP, FileTypeStrategy::new(Family::GZIP).family());
+    }
+
+    #[test]
+    fn parse_png() {
+        let report_card = FileTypeStrategy::new(Family::PNG).parse(&PNG.into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, "PNG image");
+        assert_eq!(report_card.notes, vec!["1x1 pixels".to_owned(), "8 bit greyscale".to_owned()]);
+    }
+
+    #[test]
+    fn parse_other_file_type() {
+        let report_card = FileTypeStrategy::new(Family::GZIP).parse(&PNG.into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.notes, Vec::<String>::new());
+    }
+
+    #[test]
+    fn text_that_happens_to_match() {
+        let report_card = FileTypeStrategy::new(Family::BZIP2).parse(&"BZh hello".into());
+        assert_eq!(report_card.sureness, 0.0);
+
+        let report_card = FileTypeStrategy::new(Family::PDF).parse(&"%PDF-1.4".into());
+        assert_eq!(report_card.sureness, 0.7);
+    }
+}
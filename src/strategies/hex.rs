@@ -0,0 +1,133 @@
+// bytes written out as hexadecimal, which plenty of base64 and base32 strings also look like
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder::{self, HexVariant};
+
+const HEX_ID_NAME: &str = "HEX";
+const FRIENDLY_NAME: &str = "Hex";
+
+pub struct HexStrategy {}
+
+// the more punctuation around the digits, the less likely it is to be anything else
+fn variant_factor(variant: HexVariant) -> Factor {
+    match variant {
+        HexVariant::Plain => Factor::new("made of an even number of hex digits, all in the same case", 0.4),
+        HexVariant::Prefixed => Factor::new("hex digits after a 0x prefix", 0.4),
+        HexVariant::ColonSeparated => Factor::new("pairs of hex digits separated by colons", 0.5),
+        HexVariant::SpaceSeparated => Factor::new("pairs of hex digits separated by spaces", 0.5),
+        HexVariant::Escaped => Factor::new("pairs of hex digits each escaped with \\x", 0.6),
+    }
+}
+
+impl StrategyType for HexStrategy {
+    fn id(&self) -> String {
+        String::from(HEX_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::HEX
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text.trim(),
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let variant = match decoder::hex_variant(input) {
+            Some(variant) => variant,
+            None => {
+                let rejected = vec![Factor::new("not made of hex digits", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let digits: Vec<char> = match variant {
+            HexVariant::Prefixed | HexVariant::Escaped => vec![],
+            _ => input.chars().filter(|c| c.is_ascii_hexdigit()).collect(),
+        };
+
+        // 42, 2024, 12:30:45 and 10 20 30 are numbers and times far more often than bytes
+        if !digits.is_empty() && digits.iter().all(|c| c.is_ascii_digit()) {
+            let rejected = vec![Factor::new("only decimal digits, so more likely a number, date or time", 0.0)];
+            return ReportCard::new_with_factors(variant.name().into(), self.family(), rejected);
+        }
+
+        // and face, cafe and facade are words, where deadbeef is a classic
+        if variant == HexVariant::Plain && input.len() < 8 && digits.iter().all(|c| c.is_ascii_alphabetic()) {
+            let rejected = vec![Factor::new("short, and only the letters a to f, so more likely a word", 0.0)];
+            return ReportCard::new_with_factors(variant.name().into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![variant_factor(variant), strategy::length_factor(input.len(), "hex")];
+
+        factors.push(strategy::decoded_factor(decoder::decode_hex(input)));
+
+        ReportCard::new_with_factors(variant.name().into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(HexStrategy {}.id(), HEX_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, HexStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(HexStrategy {}.family(), families::Family::HEX);
+    }
+
+    #[test]
+    fn parse_text() {
+        let report_card = HexStrategy {}.parse(&"68656c6c6f20776f726c64".into());
+        assert_eq!(report_card.sureness, 0.9);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_variants() {
+        let escaped = HexStrategy {}.parse(&"\\xde\\xad\\xbe\\xef".into());
+        assert_eq!(escaped.type_friendly_name, "Hex (\\x escaped)");
+        assert_eq!(escaped.sureness, 0.7);
+
+        let separated = HexStrategy {}.parse(&"de:ad:be:ef".into());
+        assert_eq!(separated.type_friendly_name, "Hex (colon separated)");
+        assert_eq!(separated.sureness, 0.5);
+    }
+
+    #[test]
+    fn parse_number() {
+        assert_eq!(HexStrategy {}.parse(&"12345678".into()).sureness, 0.0);
+        assert_eq!(HexStrategy {}.parse(&"42".into()).sureness, 0.0);
+        assert_eq!(HexStrategy {}.parse(&"12:30:45".into()).sureness, 0.0);
+        assert_eq!(HexStrategy {}.parse(&"10 20 30".into()).sureness, 0.0);
+        assert_eq!(HexStrategy {}.parse(&"0x10".into()).sureness, 0.2);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        let report_card = HexStrategy {}.parse(&"potato".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+
+        assert_eq!(HexStrategy {}.parse(&"face".into()).sureness, 0.0);
+        assert_eq!(HexStrategy {}.parse(&"facade".into()).sureness, 0.0);
+    }
+}
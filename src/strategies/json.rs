@@ -1,14 +1,88 @@
 use families;
-use strategies::strategy::StrategyType;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
 use ReportCard;
+use report_card::{Factor, ParseError};
 use serde_json;
 use serde_json::{Error, Value};
+use serde_json::error::Category;
 
 const JSON_ID_NAME: &'static str = "JSON_ONLY";
 const FRIENDLY_NAME: &'static str = "JSON";
+const NEAR_MISS_NAME: &str = "almost JSON";
 
 pub struct JsonStrategy {}
 
+// documents are objects or arrays; a bare scalar is valid JSON but rarely meant as JSON
+fn shape_factor(value: &Value) -> Factor {
+    match *value {
+        Value::Object(_) => Factor::new("top level is an object", 0.8),
+        Value::Array(_) => Factor::new("top level is an array", 0.8),
+        Value::String(_) => Factor::new("top level is a bare string", 0.1),
+        Value::Number(_) => Factor::new("top level is a bare number", 0.0),
+        Value::Bool(_) => Factor::new("top level is a bare boolean", 0.0),
+        Value::Null => Factor::new("top level is a bare null", 0.0),
+    }
+}
+
+// the character serde_json stopped at; its columns are 1-based bytes, and 0 means before the line
+fn char_at(input: &str, line: usize, col: usize) -> Option<char> {
+    let text = input.lines().nth(line.checked_sub(1)?)?;
+    text.get(col.saturating_sub(1)..).and_then(|rest| rest.chars().next())
+}
+
+// serde_json counts columns in bytes, ParseError in characters
+fn char_column(input: &str, line: usize, col: usize) -> usize {
+    input.lines()
+         .nth(line.saturating_sub(1))
+         .and_then(|text| text.get(..col.saturating_sub(1)))
+         .map(|before| before.chars().count() + 1)
+         .unwrap_or(col)
+}
+
+// serde_json appends " at line 3 column 17" to its messages, which ParseError already carries
+fn error_message(error: &Error) -> String {
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    message.trim_end_matches(suffix.as_str()).to_owned()
+}
+
+// the usual ways hand written or cut off JSON goes wrong
+fn mistake_factor(input: &str, error: &Error) -> Option<Factor> {
+    let message = error_message(error);
+    let found = char_at(input, error.line(), error.column());
+
+    if error.classify() == Category::Eof {
+        Some(Factor::new("looks like JSON that was cut off", 0.2))
+    } else if message == "trailing comma" {
+        Some(Factor::new("looks like JSON with a trailing comma", 0.2))
+    } else if found == Some('\'') {
+        Some(Factor::new("looks like JSON with single quoted strings", 0.2))
+    } else if message == "key must be a string" && found.map(|c| c.is_alphabetic() || c == '_').unwrap_or(false) {
+        Some(Factor::new("looks like JSON with unquoted keys", 0.2))
+    } else {
+        None
+    }
+}
+
+// broken JSON is only worth reporting if it was trying to be a document
+fn near_miss(input: &str, error: &Error) -> ReportCard {
+    let trimmed = input.trim_start();
+
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        let factors = vec![Factor::new("doesn't parse as JSON", 0.0)];
+        return ReportCard::new_with_factors(FRIENDLY_NAME.into(), families::Family::JSON, factors);
+    }
+
+    let mut factors = vec![Factor::new("starts like a JSON object or array but doesn't parse", 0.3)];
+    factors.extend(mistake_factor(input, error));
+
+    let mut card = ReportCard::new_with_factors(NEAR_MISS_NAME.into(), families::Family::JSON, factors);
+    let col = char_column(input, error.line(), error.column());
+    card.known_errors.push(ParseError::new(&error_message(error), error.line() as u32, col as u32));
+    card
+}
+
 impl StrategyType for JsonStrategy {
 
     fn id(&self) -> String {
@@ -23,12 +97,20 @@ impl StrategyType for JsonStrategy {
         families::Family::JSON
     }
 
-    fn parse(&self, input: &str) -> ReportCard {
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
         let parsed: Result<Value, Error> = serde_json::from_str(input);
-        if parsed.is_ok() {
-            ReportCard::new(1.0, FRIENDLY_NAME.into(), self.family())
-        } else {
-            ReportCard::new(0.0, FRIENDLY_NAME.into(), self.family())
+
+        match parsed {
+            Ok(value) => {
+                let factors = vec![Factor::new("parses as JSON", 0.2), shape_factor(&value)];
+                ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+            }
+            Err(e) => near_miss(input, &e),
         }
     }
 }
@@ -61,22 +143,77 @@ mod tests {
         }
         "#;
         let strategy = JsonStrategy {};
-        let report_card = strategy.parse(json);
+        let report_card = strategy.parse(&json.into());
         assert_eq!(report_card.family, strategy.family());
         assert_eq!(report_card.known_errors, vec![]);
         assert_eq!(report_card.sureness, 1.0);
         assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
     }
 
+    #[test]
+    fn parse_bare_scalars() {
+        let strategy = JsonStrategy {};
+        assert_eq!(strategy.parse(&"\"hello\"".into()).sureness, 0.3);
+        assert_eq!(strategy.parse(&"1234567890".into()).sureness, 0.2);
+        assert_eq!(strategy.parse(&"true".into()).sureness, 0.2);
+        assert_eq!(strategy.parse(&"[1, 2]".into()).sureness, 1.0);
+    }
+
     #[test]
     fn parse_invalid_json() {
         let json = "notjson";
         let strategy = JsonStrategy {};
-        let report_card = strategy.parse(json);
+        let report_card = strategy.parse(&json.into());
         assert_eq!(report_card.family, strategy.family());
         assert_eq!(report_card.known_errors, vec![]);
         assert_eq!(report_card.sureness, 0.0);
         assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn parse_trailing_comma() {
+        let json = "{\n  \"a\": 1,\n  \"b\": 2,\n}";
+        let report_card = JsonStrategy {}.parse(&json.into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.type_friendly_name, NEAR_MISS_NAME);
+        assert_eq!(report_card.known_errors, vec![ParseError::new("trailing comma", 4, 1)]);
+    }
+
+    #[test]
+    fn parse_single_quotes() {
+        let report_card = JsonStrategy {}.parse(&"{'a': 1}".into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.factors[1].reason, "looks like JSON with single quoted strings");
+        assert_eq!((report_card.known_errors[0].line, report_card.known_errors[0].col), (1, 2));
+    }
+
+    #[test]
+    fn parse_unquoted_keys() {
+        let report_card = JsonStrategy {}.parse(&"{\n  a: 1\n}".into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.factors[1].reason, "looks like JSON with unquoted keys");
+        assert_eq!(report_card.known_errors, vec![ParseError::new("key must be a string", 2, 3)]);
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let report_card = JsonStrategy {}.parse(&"{\"é\": 1,}".into());
+        assert_eq!(report_card.known_errors, vec![ParseError::new("trailing comma", 1, 9)]);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let report_card = JsonStrategy {}.parse(&"[1, 2, {\"a\": ".into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.factors[1].reason, "looks like JSON that was cut off");
+        assert_eq!(report_card.known_errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_other_broken_documents() {
+        let report_card = JsonStrategy {}.parse(&"{\"a\" 1}".into());
+        assert_eq!(report_card.sureness, 0.3);
+        assert_eq!(report_card.known_errors, vec![ParseError::new("expected `:`", 1, 6)]);
+    }
+
+}
@@ -1,3 +1,18 @@
 pub mod strategy;
 pub mod json;
 pub mod base64;
+pub mod jwt;
+pub mod self_describing_json;
+pub mod binary;
+pub mod file_type;
+pub mod compressed;
+pub mod base32;
+pub mod base58;
+pub mod ascii85;
+pub mod z85;
+pub mod hex;
+pub mod xml;
+pub mod yaml;
+pub mod toml;
+pub mod csv;
+pub mod url;
@@ -1 +1,208 @@
-// work out if something is self describing json
\ No newline at end of file
+// work out if something is self describing json
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::{Factor, ParseError};
+use serde_json::{self, Value};
+use iglu::{self, Registry};
+
+const SDJ_ID_NAME: &str = "SELF_DESCRIBING_JSON";
+const PARENT_ID_NAME: &str = "JSON_ONLY";
+const FRIENDLY_NAME: &str = "Self-describing JSON";
+
+#[derive(Default)]
+pub struct SelfDescribingJsonStrategy {
+    pub registry: Option<Registry>,
+}
+
+// problems with the envelopes themselves, and with their data according to the registry
+#[derive(Default)]
+struct Findings {
+    bad_uris: Vec<ParseError>,
+    violations: Vec<ParseError>,
+}
+
+impl SelfDescribingJsonStrategy {
+    pub fn new(registry: Option<Registry>) -> Self {
+        SelfDescribingJsonStrategy { registry }
+    }
+
+    // check this envelope and every envelope nested inside its data, e.g. contexts and unstruct_event
+    fn validate(&self, input: &str, schema: &str, data: &Value, findings: &mut Findings) {
+        let schema_text = format!("\"{}\"", schema);
+
+        match iglu::parse(schema) {
+            Err(message) => findings.bad_uris.push(ParseError::at(&message, input, &schema_text)),
+            Ok(key) => {
+                if let Some(ref registry) = self.registry {
+                    match registry.validate(&key, data) {
+                        Ok(violations) => {
+                            for v in violations {
+                                let pointer = if v.pointer.is_empty() { "/" } else { v.pointer.as_str() };
+                                let message = format!("{} data at {}: {}", key, pointer, v.message);
+                                findings.violations.push(ParseError::at(&message, input, &schema_text));
+                            }
+                        }
+                        Err(message) => findings.violations.push(ParseError::at(&message, input, &schema_text)),
+                    }
+                }
+            }
+        }
+
+        let nested: Vec<&Value> = match *data {
+            Value::Array(ref items) => items.iter().collect(),
+            ref other => vec![other],
+        };
+
+        for item in nested {
+            if let Some((schema, data)) = iglu::envelope(item) {
+                self.validate(input, schema, data, findings);
+            }
+        }
+    }
+}
+
+impl StrategyType for SelfDescribingJsonStrategy {
+    fn id(&self) -> String {
+        String::from(SDJ_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        Some(String::from(PARENT_ID_NAME))
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::SELF_DESCRIBING_JSON
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let parsed: Option<Value> = serde_json::from_str(input).ok();
+
+        let (schema, data) = match parsed.as_ref().and_then(iglu::envelope) {
+            Some(envelope) => envelope,
+            None => {
+                let rejected = vec![Factor::new("not a JSON object with just schema and data", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let mut findings = Findings::default();
+        self.validate(input, schema, data, &mut findings);
+
+        let mut factors = vec![Factor::new("a JSON object with just schema and data", 0.5)];
+
+        // shaped like an envelope, but not one the rest of the pipeline would accept
+        if findings.bad_uris.is_empty() {
+            factors.push(Factor::new("every schema is a valid Iglu URI", 0.5));
+        } else {
+            factors.push(Factor::new("has schemas that aren't valid Iglu URIs", 0.0));
+        }
+
+        let mut card = ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors);
+
+        card.known_errors = findings.bad_uris;
+        card.known_errors.extend(findings.violations);
+
+        card
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use families;
+
+    #[test]
+    fn id() {
+        assert_eq!(SelfDescribingJsonStrategy::default().id(), SDJ_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(Some(PARENT_ID_NAME.to_owned()), SelfDescribingJsonStrategy::default().child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(SelfDescribingJsonStrategy::default().family(), families::Family::SELF_DESCRIBING_JSON);
+    }
+
+    #[test]
+    fn parse_good() {
+        let sdj = r#"{"schema": "iglu:com.acme/my_event/jsonschema/1-0-0", "data": {"hello": "world"}}"#;
+        let report_card = SelfDescribingJsonStrategy::default().parse(&sdj.into());
+        assert_eq!(report_card.family, SelfDescribingJsonStrategy::default().family());
+        assert_eq!(report_card.known_errors, vec![]);
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_nested_contexts() {
+        let sdj = r#"{
+            "schema": "iglu:com.snowplowanalytics.snowplow/contexts/jsonschema/1-0-0",
+            "data": [
+                {"schema": "iglu:com.acme/context/jsonschema/1-0-0", "data": {}},
+                {"schema": "iglu:com.acme/broken/jsonschema/1-0", "data": {}}
+            ]
+        }"#;
+        let report_card = SelfDescribingJsonStrategy::default().parse(&sdj.into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.known_errors,
+                   vec![ParseError::new("version '1-0' should be MODEL-REVISION-ADDITION", 5, 28)]);
+    }
+
+    #[test]
+    fn parse_bad_uri() {
+        let sdj = r#"{"schema": "com.acme/my_event", "data": {}}"#;
+        let report_card = SelfDescribingJsonStrategy::default().parse(&sdj.into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.known_errors,
+                   vec![ParseError::new("schema 'com.acme/my_event' should start with 'iglu:'", 1, 12)]);
+    }
+
+    fn with_registry() -> SelfDescribingJsonStrategy {
+        SelfDescribingJsonStrategy::new(Some(Registry::new("tests/resources/iglu")))
+    }
+
+    #[test]
+    fn parse_valid_against_registry() {
+        let sdj = r#"{"schema": "iglu:com.acme/link_click/jsonschema/1-0-0", "data": {"targetUrl": "http://acme.com"}}"#;
+        let report_card = with_registry().parse(&sdj.into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.known_errors, vec![]);
+    }
+
+    #[test]
+    fn parse_invalid_against_registry() {
+        let sdj = r#"{
+            "schema": "iglu:com.snowplowanalytics.snowplow/unstruct_event/jsonschema/1-0-0",
+            "data": {"schema": "iglu:com.acme/link_click/jsonschema/1-0-0", "data": {"targetUrl": ""}}
+        }"#;
+        let report_card = with_registry().parse(&sdj.into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.known_errors.len(), 2);
+
+        // the wrapper isn't in the test registry
+        assert!(report_card.known_errors[0].message.starts_with("couldn't find iglu:com.snowplowanalytics.snowplow/unstruct_event"));
+        assert_eq!((report_card.known_errors[0].line, report_card.known_errors[0].col), (2, 23));
+
+        assert!(report_card.known_errors[1].message.starts_with("iglu:com.acme/link_click/jsonschema/1-0-0 data at /targetUrl: "));
+        assert_eq!((report_card.known_errors[1].line, report_card.known_errors[1].col), (3, 32));
+    }
+
+    #[test]
+    fn parse_plain_json() {
+        let report_card = SelfDescribingJsonStrategy::default().parse(&r#"{"hello": "world"}"#.into());
+        assert_eq!(report_card.known_errors, vec![]);
+        assert_eq!(report_card.sureness, 0.0);
+    }
+
+}
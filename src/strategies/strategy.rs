@@ -6,12 +6,65 @@
 
 use families;
 use ReportCard;
+use report_card::Factor;
+use input::Input;
+use magic;
 
 pub trait StrategyType {
     fn id(&self) -> String;
     fn child_of_id(&self) -> Option<String>;
     fn family(&self) -> families::Family;
-    fn parse(&self, &str) -> ReportCard;
+    fn parse(&self, input: &Input) -> ReportCard;
+}
+
+// mostly readable text, allowing for the usual whitespace
+fn is_printable(text: &str) -> bool {
+    let total = text.chars().count();
+    let printable = text.chars().filter(|c| !c.is_control() || *c == '\n' || *c == '\r' || *c == '\t').count();
+    total > 0 && printable * 10 >= total * 9
+}
+
+// runs of letters and digits shorter than this are as likely to be words, names or numbers as an encoding
+pub const UNLIKELY_PROSE_LENGTH: usize = 20;
+
+// a recognised file or plain ASCII text, rather than the noise an ordinary word decodes to, which can
+// happen to be a character or two of valid UTF-8
+pub fn makes_sense(decoded: &[u8]) -> bool {
+    let readable = decoded.iter().filter(|b| b.is_ascii_graphic() || b.is_ascii_whitespace()).count();
+    magic::identify(decoded).is_some() || (!decoded.is_empty() && readable * 10 >= decoded.len() * 9)
+}
+
+// the longer a binary-to-text encoding is, the less likely it is to be a word that happens to fit
+pub fn length_factor(length: usize, encoding: &str) -> Factor {
+    if length >= 16 {
+        Factor::new("long enough to be deliberate", 0.2)
+    } else if length >= 8 {
+        Factor::new("fairly short", 0.1)
+    } else {
+        Factor::new(&format!("very short, so plenty of words happen to be valid {}", encoding), -0.1)
+    }
+}
+
+// what a binary-to-text encoding decodes to says a lot about whether it really is one
+pub fn decoded_factor(decoded: Option<Vec<u8>>) -> Factor {
+    match decoded {
+        Some(bytes) => {
+            if let Some(name) = magic::identify(&bytes) {
+                Factor::new(&format!("decodes to a {}", name), 0.3)
+            } else {
+                match String::from_utf8(bytes) {
+                    Ok(ref text) if is_printable(text) => Factor::new("decodes to printable text", 0.3),
+                    _ => Factor::new("decodes to unrecognised binary", -0.1),
+                }
+            }
+        }
+        None => Factor::new("doesn't decode", -1.0),
+    }
+}
+
+// the report card for a text format given bytes that aren't text
+pub fn not_text(type_friendly_name: &str, family: families::Family) -> ReportCard {
+    ReportCard::new_with_factors(type_friendly_name.into(), family, vec![Factor::new("isn't UTF-8 text", 0.0)])
 }
 
 #[cfg(test)]
@@ -33,7 +86,7 @@ mod tests {
             families::Family::UNSTRUCTURED
         }
 
-        fn parse(&self, _: &str) -> ReportCard {
+        fn parse(&self, _: &Input) -> ReportCard {
             ReportCard::new(0.0, "none".into(), self.family())
         }
     }
@@ -58,8 +111,38 @@ mod tests {
 
     #[test]
     fn parse() {
-        let parsed = TestStrategy {}.parse("hello world");
+        let parsed = TestStrategy {}.parse(&"hello world".into());
         assert_eq!(parsed,
                    ReportCard::new(0.0, "none".into(), TestStrategy {}.family()))
     }
+
+    #[test]
+    fn printable() {
+        assert!(is_printable("hello\nworld"));
+        assert!(!is_printable("\u{1}\u{2}\u{3}"));
+        assert!(!is_printable(""));
+    }
+
+    #[test]
+    fn decoded() {
+        assert_eq!(Factor::new("decodes to printable text", 0.3), decoded_factor(Some(b"hello".to_vec())));
+        assert_eq!(Factor::new("decodes to a gzip data", 0.3), decoded_factor(Some(b"\x1f\x8b\x08".to_vec())));
+        assert_eq!(Factor::new("decodes to unrecognised binary", -0.1), decoded_factor(Some(vec![0xff, 0x00])));
+        assert_eq!(Factor::new("doesn't decode", -1.0), decoded_factor(None));
+    }
+
+    #[test]
+    fn sense() {
+        assert!(makes_sense(b"hello world"));
+        assert!(makes_sense(b"\x1f\x8b\x08"));
+        assert!(!makes_sense("r\u{6e7}".as_bytes()));
+        assert!(!makes_sense(b""));
+    }
+
+    #[test]
+    fn binary_is_not_text() {
+        let card = not_text("JSON", families::Family::JSON);
+        assert_eq!(card.sureness, 0.0);
+        assert_eq!(card.factors, vec![Factor::new("isn't UTF-8 text", 0.0)]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,176 @@
+// TOML, as pasted from Cargo.toml and other config files
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::{self, Factor, ParseError};
+use toml::{self, Table, Value};
+
+const TOML_ID_NAME: &str = "TOML";
+const FRIENDLY_NAME: &str = "TOML";
+const NEAR_MISS_NAME: &str = "almost TOML";
+
+pub struct TomlStrategy {}
+
+// toml gives a byte range for where it went wrong, and its message on its own
+fn parse_error(input: &str, error: &toml::de::Error) -> ParseError {
+    let (line, col) = error.span()
+                           .map(|span| report_card::position_at(input, span.start))
+                           .unwrap_or((0, 0));
+    ParseError::new(error.message().trim(), line, col)
+}
+
+fn is_table_header(line: &str) -> bool {
+    line.starts_with('[') && line.ends_with(']') && line.len() > 2
+}
+
+// key = value, where the key is bare or quoted
+fn is_assignment(line: &str) -> bool {
+    match line.find('=') {
+        Some(equals) => {
+            let key = line[..equals].trim();
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || "_-.\"'".contains(c))
+        }
+        None => false,
+    }
+}
+
+fn content_lines(input: &str) -> Vec<&str> {
+    input.lines()
+         .map(|l| l.trim())
+         .filter(|l| !l.is_empty() && !l.starts_with('#'))
+         .collect()
+}
+
+fn key_count(table: &Table) -> usize {
+    table.values()
+         .map(|value| match *value {
+             Value::Table(ref nested) => key_count(nested),
+             _ => 1,
+         })
+         .sum()
+}
+
+impl StrategyType for TomlStrategy {
+    fn id(&self) -> String {
+        String::from(TOML_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::TOML
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let lines = content_lines(input);
+        let headers = lines.iter().any(|l| is_table_header(l));
+
+        // a=1 on its own is as much a query string or a shell variable as it is TOML
+        if lines.len() == 1 && !headers {
+            let rejected = vec![Factor::new("a single line with no [table] header", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let table: Table = match input.parse() {
+            Ok(table) => table,
+            Err(error) => {
+                // an INI file or a typo in a config file, rather than prose with an = in it
+                if headers || lines.first().map(|l| is_assignment(l)).unwrap_or(false) {
+                    let mut factors = vec![Factor::new("laid out like TOML but doesn't parse", 0.3)];
+
+                    // [section] is a broken JSON array too, but far more often an INI or TOML header
+                    if headers {
+                        factors.push(Factor::new("has [table] headers", 0.1));
+                    }
+
+                    let mut card = ReportCard::new_with_factors(NEAR_MISS_NAME.into(), self.family(), factors);
+                    card.known_errors.push(parse_error(input, &error));
+                    return card;
+                }
+
+                let rejected = vec![Factor::new("doesn't parse as TOML", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        if table.is_empty() {
+            let rejected = vec![Factor::new("parses as TOML, but so does any blank text", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![Factor::new("parses as TOML", 0.3)];
+
+        if headers {
+            factors.push(Factor::new("has [table] headers", 0.4));
+        }
+
+        factors.push(match key_count(&table) {
+            1 => Factor::new("only one key", 0.0),
+            _ => Factor::new("more than one key", 0.2),
+        });
+
+        ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(TomlStrategy {}.id(), TOML_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, TomlStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(TomlStrategy {}.family(), families::Family::TOML);
+    }
+
+    #[test]
+    fn parse_cargo_toml() {
+        let cargo = "[package]\nname = \"scrutey\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde_json = \"1.0\"\n";
+        let report_card = TomlStrategy {}.parse(&cargo.into());
+        assert_eq!(report_card.sureness, 0.9);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_snippet() {
+        assert_eq!(TomlStrategy {}.parse(&"a = 1\nb = [true, false]".into()).sureness, 0.5);
+        assert_eq!(TomlStrategy {}.parse(&"[a]\nb = 1".into()).sureness, 0.7);
+    }
+
+    #[test]
+    fn parse_broken() {
+        let report_card = TomlStrategy {}.parse(&"[package]\nname = scrutey\n".into());
+        assert_eq!(report_card.sureness, 0.4);
+        assert_eq!(report_card.type_friendly_name, NEAR_MISS_NAME);
+        assert_eq!((report_card.known_errors[0].line, report_card.known_errors[0].col), (2, 8));
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(TomlStrategy {}.parse(&"hello world".into()).sureness, 0.0);
+        assert_eq!(TomlStrategy {}.parse(&"# just a comment".into()).sureness, 0.0);
+        assert_eq!(TomlStrategy {}.parse(&"if x = 1 then".into()).sureness, 0.0);
+
+        for line in ["a=1", "a = 1", "key=value", "# a comment\nkey=value\n"] {
+            assert_eq!(TomlStrategy {}.parse(&line.into()).sureness, 0.0);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+// absolute URLs and bare query strings, as copied from a browser or a tracking pixel
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder;
+
+const URL_ID_NAME: &str = "URL";
+const FRIENDLY_NAME: &str = "URL";
+const QUERY_STRING_NAME: &str = "query string";
+
+const WEB_SCHEMES: [&str; 6] = ["http", "https", "ftp", "ws", "wss", "file"];
+
+pub struct UrlStrategy {}
+
+impl UrlStrategy {
+    fn parse_query_string(&self, input: &str, address: &decoder::Address) -> ReportCard {
+        // aGk= and abc== are one key with nothing much after it, and far more likely padded base64
+        if address.query.len() == 1 && address.query[0].1.chars().all(|c| c == '=') {
+            let rejected = vec![Factor::new("a single key with no value looks more like base64 padding", 0.0)];
+            return ReportCard::new_with_factors(QUERY_STRING_NAME.into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![Factor::new("key=value pairs joined by &", 0.3)];
+
+        factors.push(match address.query.len() {
+            1 => Factor::new("only one pair", 0.0),
+            _ => Factor::new("more than one pair", 0.3),
+        });
+
+        if input.starts_with('?') {
+            factors.push(Factor::new("starts with ?", 0.2));
+        }
+
+        ReportCard::new_with_factors(QUERY_STRING_NAME.into(), self.family(), factors)
+    }
+}
+
+impl StrategyType for UrlStrategy {
+    fn id(&self) -> String {
+        String::from(URL_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::URL
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        // a URL in a sentence is prose, and the url crate would happily encode the spaces
+        let address = match decoder::decode_url(input) {
+            Some(ref address) if !input.contains(char::is_whitespace) => address.clone(),
+            _ => {
+                let rejected = vec![Factor::new("neither an absolute URL nor a query string", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let scheme = match address.scheme {
+            Some(ref scheme) => scheme,
+            None => return self.parse_query_string(input, &address),
+        };
+
+        let mut factors = vec![Factor::new("parses as an absolute URL with a host", 0.5)];
+
+        if WEB_SCHEMES.contains(&scheme.as_str()) {
+            factors.push(Factor::new(&format!("uses the well-known {} scheme", scheme), 0.3));
+        }
+
+        if !address.path.is_empty() {
+            factors.push(Factor::new("has a path", 0.1));
+        }
+
+        if !address.query.is_empty() {
+            factors.push(Factor::new("has query parameters", 0.1));
+        }
+
+        ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(UrlStrategy {}.id(), URL_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, UrlStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(UrlStrategy {}.family(), families::Family::URL);
+    }
+
+    #[test]
+    fn parse_url() {
+        let report_card = UrlStrategy {}.parse(&"https://example.com/search?q=scrutey".into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+
+        assert_eq!(UrlStrategy {}.parse(&"https://example.com".into()).sureness, 0.8);
+        assert_eq!(UrlStrategy {}.parse(&"gopher://example.com".into()).sureness, 0.5);
+    }
+
+    #[test]
+    fn parse_query_string() {
+        let report_card = UrlStrategy {}.parse(&"e=pv&page=Home%20page&tv=js-2.0".into());
+        assert_eq!(report_card.sureness, 0.6);
+        assert_eq!(report_card.type_friendly_name, QUERY_STRING_NAME);
+
+        assert_eq!(UrlStrategy {}.parse(&"?a=1&b=2".into()).sureness, 0.8);
+        assert_eq!(UrlStrategy {}.parse(&"a=1".into()).sureness, 0.3);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(UrlStrategy {}.parse(&"hello world".into()).sureness, 0.0);
+        assert_eq!(UrlStrategy {}.parse(&"see https://example.com/ for more".into()).sureness, 0.0);
+        assert_eq!(UrlStrategy {}.parse(&"aGk=".into()).sureness, 0.0);
+        assert_eq!(UrlStrategy {}.parse(&"aGVsbG8gd29ybGQ==".into()).sureness, 0.0);
+        assert_eq!(UrlStrategy {}.parse(&"mailto:someone@example.com".into()).sureness, 0.0);
+    }
+}
@@ -0,0 +1,129 @@
+// XML documents, from SOAP bodies to config files, which only count if they're well-formed
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use xml::{self, Node};
+
+const XML_ID_NAME: &str = "XML";
+const FRIENDLY_NAME: &str = "XML";
+const NEAR_MISS_NAME: &str = "almost XML";
+
+pub struct XmlStrategy {}
+
+fn declaration_factor(nodes: &[Node]) -> Factor {
+    match nodes.first() {
+        Some(Node::Instruction(i)) if i.starts_with("xml ") => Factor::new("starts with an <?xml ?> declaration", 0.2),
+        _ => Factor::new("has no <?xml ?> declaration", 0.0),
+    }
+}
+
+// a lone tag could be a scrap of HTML or a placeholder; a tree is deliberate
+fn structure_factor(nodes: &[Node]) -> Factor {
+    match xml::root(nodes) {
+        Some(Node::Element { children, .. }) if children.iter().any(|c| matches!(c, Node::Element { .. })) => {
+            Factor::new("has nested elements", 0.2)
+        }
+        _ => Factor::new("is a single element", 0.0),
+    }
+}
+
+impl StrategyType for XmlStrategy {
+    fn id(&self) -> String {
+        String::from(XML_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::XML
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let trimmed = input.trim_start_matches('\u{feff}').trim_start();
+
+        if !trimmed.starts_with('<') {
+            let rejected = vec![Factor::new("doesn't start with <", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        match xml::parse(input) {
+            Ok(nodes) => {
+                let factors = vec![Factor::new("is well-formed XML", 0.6),
+                                   declaration_factor(&nodes),
+                                   structure_factor(&nodes)];
+                ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+            }
+            Err(error) => {
+                let mut factors = vec![Factor::new("starts like XML but isn't well-formed", 0.3)];
+
+                if trimmed.starts_with("<?xml ") {
+                    factors.push(Factor::new("starts with an <?xml ?> declaration", 0.2));
+                }
+
+                let mut card = ReportCard::new_with_factors(NEAR_MISS_NAME.into(), self.family(), factors);
+                card.known_errors.push(error);
+                card
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use report_card::ParseError;
+
+    #[test]
+    fn id() {
+        assert_eq!(XmlStrategy {}.id(), XML_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, XmlStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(XmlStrategy {}.family(), families::Family::XML);
+    }
+
+    #[test]
+    fn parse_good() {
+        let xml = "<?xml version=\"1.0\"?>\n<config><name>scrutey</name></config>";
+        let report_card = XmlStrategy {}.parse(&xml.into());
+        assert_eq!(report_card.sureness, 1.0);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+        assert_eq!(report_card.known_errors, vec![]);
+    }
+
+    #[test]
+    fn parse_single_element() {
+        assert_eq!(XmlStrategy {}.parse(&"<br/>".into()).sureness, 0.6);
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let report_card = XmlStrategy {}.parse(&"<?xml version=\"1.0\"?>\n<a><b></a>".into());
+        assert_eq!(report_card.sureness, 0.5);
+        assert_eq!(report_card.type_friendly_name, NEAR_MISS_NAME);
+        assert_eq!(report_card.known_errors, vec![ParseError::new("expected 'b' tag, not 'a'", 2, 7)]);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        let report_card = XmlStrategy {}.parse(&"a < b".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.known_errors, vec![]);
+    }
+}
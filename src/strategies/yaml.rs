@@ -0,0 +1,199 @@
+// YAML documents, scored so that prose, which is nearly always valid YAML, doesn't pass for it
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use serde_yaml::Value;
+use yaml;
+
+const YAML_ID_NAME: &str = "YAML";
+const FRIENDLY_NAME: &str = "YAML";
+const NEAR_MISS_NAME: &str = "almost YAML";
+
+pub struct YamlStrategy {}
+
+// any text at all is a YAML scalar, so only collections say much
+fn shape_factor(documents: &[Value]) -> Factor {
+    match documents.iter().find(|d| yaml::is_collection(d)).map(yaml::untagged) {
+        Some(Value::Mapping(_)) => Factor::new("has a mapping at the top level", 0.3),
+        Some(_) => Factor::new("has a sequence at the top level", 0.3),
+        None => Factor::new("only bare scalars, which any text is", -0.2),
+    }
+}
+
+// "Note: this" is a one entry mapping, and so is plenty of other prose
+fn size_factor(documents: &[Value]) -> Factor {
+    match documents.iter().map(|d| yaml::children(d).len()).max().unwrap_or(0) {
+        0 | 1 => Factor::new("only one entry, as a line of prose with a colon would be", -0.5),
+        _ => Factor::new("more than one entry", 0.1),
+    }
+}
+
+// keys in config are identifiers; keys in prose are phrases
+fn key_factor(documents: &[Value]) -> Option<Factor> {
+    let keys: Vec<&str> = documents.iter()
+        .filter_map(|d| yaml::untagged(d).as_mapping())
+        .flat_map(|entries| entries.keys().filter_map(Value::as_str))
+        .collect();
+    let phrases = keys.iter().filter(|k| k.contains(' ')).count();
+
+    if !keys.is_empty() && phrases * 2 >= keys.len() {
+        Some(Factor::new("most keys have spaces in, like prose", -0.6))
+    } else {
+        None
+    }
+}
+
+fn nested(documents: &[Value]) -> bool {
+    documents.iter().any(|d| yaml::children(d).iter().any(|c| yaml::is_collection(c)))
+}
+
+// the first thing that isn't a comment, a directive or blank
+fn first_content(input: &str) -> &str {
+    input.lines()
+         .map(|l| l.trim())
+         .find(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('%'))
+         .unwrap_or("")
+}
+
+// lines before the one that broke that were a key: value or a list item, as YAML's are
+fn structured_lines(input: &str, before: u32) -> usize {
+    input.lines()
+         .take((before as usize).saturating_sub(1))
+         .map(|l| l.trim())
+         .filter(|l| l.starts_with("- ") || yaml::split_key(l).is_some())
+         .count()
+}
+
+impl StrategyType for YamlStrategy {
+    fn id(&self) -> String {
+        String::from(YAML_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::YAML
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text,
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let first = first_content(input);
+
+        let documents = match yaml::parse(input) {
+            Ok(documents) => documents,
+            Err(error) => {
+                // only worth reporting if it was YAML for a while, not prose with a colon in
+                if first == "---" || structured_lines(input, error.line) >= 2 {
+                    let factors = vec![Factor::new("starts like YAML but doesn't parse", 0.3)];
+                    let mut card = ReportCard::new_with_factors(NEAR_MISS_NAME.into(), self.family(), factors);
+                    card.known_errors.push(error);
+                    return card;
+                }
+
+                let rejected = vec![Factor::new("doesn't parse as YAML", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        let mut factors = vec![Factor::new("parses as YAML", 0.2), shape_factor(&documents), size_factor(&documents)];
+        factors.extend(key_factor(&documents));
+
+        if nested(&documents) {
+            factors.push(Factor::new("has nested collections", 0.2));
+        }
+
+        if first.starts_with("---") {
+            factors.push(Factor::new("starts with a --- document marker", 0.1));
+        }
+
+        if documents.iter().any(yaml::has_tags) {
+            factors.push(Factor::new("uses tags", 0.1));
+        }
+
+        if first.starts_with('{') || first.starts_with('[') {
+            factors.push(Factor::new("written in flow style, which JSON explains better", -0.3));
+        }
+
+        let name = match documents.len() {
+            1 => FRIENDLY_NAME.to_owned(),
+            n => format!("YAML ({} documents)", n),
+        };
+
+        ReportCard::new_with_factors(name, self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use report_card::ParseError;
+
+    const DEPLOYMENT: &str = "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: web\n  labels: &labels\n    app: web\nspec:\n  selector:\n    matchLabels: *labels\n  template:\n    spec:\n      containers:\n      - name: web\n        image: nginx:1.25\n";
+
+    #[test]
+    fn id() {
+        assert_eq!(YamlStrategy {}.id(), YAML_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, YamlStrategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(YamlStrategy {}.family(), families::Family::YAML);
+    }
+
+    #[test]
+    fn parse_config() {
+        let report_card = YamlStrategy {}.parse(&DEPLOYMENT.into());
+        assert_eq!(report_card.sureness, 0.8);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_multiple_documents() {
+        let report_card = YamlStrategy {}.parse(&"---\na: 1\nb: 2\n---\nc: 3\n".into());
+        assert_eq!(report_card.sureness, 0.7);
+        assert_eq!(report_card.type_friendly_name, "YAML (2 documents)");
+    }
+
+    #[test]
+    fn prose_with_a_colon_isnt_almost_yaml() {
+        let report_card = YamlStrategy {}.parse(&"Note: hello\nThis is fine".into());
+        assert_eq!(report_card.sureness, 0.0);
+        assert_eq!(report_card.known_errors, vec![]);
+    }
+
+    #[test]
+    fn prose_isnt_sure() {
+        for prose in ["hello world", "Hello there.\nThis is just some prose.", "Note: this is prose",
+                      "Dear team: thanks\nBest wishes: me", "1234", "hello: world: foo"].iter() {
+            assert_eq!(YamlStrategy {}.parse(&(*prose).into()).sureness, 0.0, "{}", prose);
+        }
+
+        // JSON is YAML too, but JSON is the better name for it
+        assert!(YamlStrategy {}.parse(&"{\"a\": 1, \"b\": 2}".into()).sureness < 0.5);
+    }
+
+    #[test]
+    fn parse_broken() {
+        let report_card = YamlStrategy {}.parse(&"a: 1\nb:\n  c: 2\n   d: 3".into());
+        assert_eq!(report_card.sureness, 0.3);
+        assert_eq!(report_card.type_friendly_name, NEAR_MISS_NAME);
+        assert_eq!(report_card.known_errors.len(), 1);
+
+        let report_card = YamlStrategy {}.parse(&"---\na: 1\na: 2".into());
+        assert_eq!(report_card.known_errors, vec![ParseError::new("duplicate entry with key \"a\"", 2, 1)]);
+    }
+}
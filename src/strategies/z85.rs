@@ -0,0 +1,109 @@
+// ZeroMQ's Z85, a string-safe take on ascii85
+
+use families;
+use strategies::strategy::{self, StrategyType};
+use input::Input;
+use ReportCard;
+use report_card::Factor;
+use decoder;
+
+const Z85_ID_NAME: &str = "Z85";
+const FRIENDLY_NAME: &str = "Z85";
+
+// the characters Z85 uses beyond letters and digits
+const PUNCTUATION: &str = ".-:+=^!/*?&<>()[]{}@%$#";
+
+// Z85 punctuation that also turns up in dates, times, numbers, base64 and markup
+const COMMON_PUNCTUATION: &str = ".-:+=/<>";
+
+pub struct Z85Strategy {}
+
+impl StrategyType for Z85Strategy {
+    fn id(&self) -> String {
+        String::from(Z85_ID_NAME)
+    }
+
+    fn child_of_id(&self) -> Option<String> {
+        None
+    }
+
+    fn family(&self) -> families::Family {
+        families::Family::Z85
+    }
+
+    fn parse(&self, input: &Input) -> ReportCard {
+        let input = match input.text() {
+            Some(text) => text.trim(),
+            None => return strategy::not_text(FRIENDLY_NAME, self.family()),
+        };
+
+        let decoded = match decoder::decode_z85(input) {
+            Some(decoded) => decoded,
+            None => {
+                let rejected = vec![Factor::new("not made of Z85 characters in groups of five", 0.0)];
+                return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+            }
+        };
+
+        // any word five letters long is valid Z85, and so is 2024-01-01, and <a><b></a>
+        let distinctive = input.chars().any(|c| PUNCTUATION.contains(c) && !COMMON_PUNCTUATION.contains(c));
+        if !distinctive && input.len() < strategy::UNLIKELY_PROSE_LENGTH {
+            let rejected = vec![Factor::new("short, and without the punctuation that sets Z85 apart from words and dates", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        if !distinctive && !strategy::makes_sense(&decoded) {
+            let rejected = vec![Factor::new("without Z85's own punctuation, and decodes to nothing that makes sense", 0.0)];
+            return ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), rejected);
+        }
+
+        let mut factors = vec![Factor::new("made of Z85 characters in groups of five", 0.2),
+                               strategy::length_factor(input.len(), "Z85")];
+
+        if input.chars().any(|c| PUNCTUATION.contains(c)) {
+            factors.push(Factor::new("uses Z85's punctuation", 0.1));
+        }
+
+        factors.push(strategy::decoded_factor(Some(decoded)));
+
+        ReportCard::new_with_factors(FRIENDLY_NAME.into(), self.family(), factors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn id() {
+        assert_eq!(Z85Strategy {}.id(), Z85_ID_NAME);
+    }
+
+    #[test]
+    fn child_of_id() {
+        assert_eq!(None, Z85Strategy {}.child_of_id());
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Z85Strategy {}.family(), families::Family::Z85);
+    }
+
+    #[test]
+    fn parse_good() {
+        // "hello world!"
+        let report_card = Z85Strategy {}.parse(&"xK#0@zY<mxA+]nf".into());
+        assert_eq!(report_card.sureness, 0.7);
+        assert_eq!(report_card.type_friendly_name, FRIENDLY_NAME);
+    }
+
+    #[test]
+    fn parse_nonsense() {
+        assert_eq!(Z85Strategy {}.parse(&"hello world".into()).sureness, 0.0);
+        assert_eq!(Z85Strategy {}.parse(&"hell".into()).sureness, 0.0);
+        assert_eq!(Z85Strategy {}.parse(&"hello".into()).sureness, 0.0);
+        assert_eq!(Z85Strategy {}.parse(&"2024-01-01".into()).sureness, 0.0);
+        assert_eq!(Z85Strategy {}.parse(&"<a><b></a>".into()).sureness, 0.0);
+        assert_eq!(Z85Strategy {}.parse(&"<a>".repeat(40).as_str().into()).sureness, 0.0);
+    }
+}
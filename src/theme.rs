@@ -0,0 +1,308 @@
+// colour themes, and how to show them on terminals that support 16, 256 or millions of colours
+
+use colour::Role;
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Depth {
+    Sixteen,
+    TwoFiftySix,
+    TrueColour,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Colour {
+    Basic(u8),
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Style {
+    pub bold: bool,
+    pub colour: Option<Colour>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Theme {
+    styles: HashMap<Role, Style>,
+}
+
+pub const BUILT_IN: [&str; 3] = ["default", "light", "dark"];
+
+const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+// xterm's defaults for the 16 basic colours
+const BASIC_RGB: [(u8, u8, u8); 16] = [(0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+                                       (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+                                       (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+                                       (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255)];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn indexed_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => BASIC_RGB[n as usize],
+        16..=231 => {
+            let i = n - 16;
+            (CUBE_LEVELS[(i / 36) as usize], CUBE_LEVELS[(i / 6 % 6) as usize], CUBE_LEVELS[(i % 6) as usize])
+        }
+        _ => {
+            let grey = 8 + 10 * (n - 232);
+            (grey, grey, grey)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn nearest(rgb: (u8, u8, u8), candidates: std::ops::RangeInclusive<u8>) -> u8 {
+    candidates.min_by_key(|n| distance(rgb, indexed_rgb(*n))).unwrap_or(0)
+}
+
+impl Colour {
+    // the closest this terminal can get
+    fn at_depth(self, depth: Depth) -> Colour {
+        match (self, depth) {
+            (Colour::Rgb(r, g, b), Depth::TwoFiftySix) => Colour::Indexed(nearest((r, g, b), 16..=255)),
+            (Colour::Rgb(r, g, b), Depth::Sixteen) => Colour::Basic(nearest((r, g, b), 0..=15)),
+            (Colour::Indexed(n), Depth::Sixteen) => Colour::Basic(nearest(indexed_rgb(n), 0..=15)),
+            (colour, _) => colour,
+        }
+    }
+
+    fn code(self) -> String {
+        match self {
+            Colour::Basic(n) if n < 8 => (30 + n).to_string(),
+            Colour::Basic(n) => (90 + n - 8).to_string(),
+            Colour::Indexed(n) => format!("38;5;{}", n),
+            Colour::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+
+    // red, bright-red, 0 to 255, or #rrggbb
+    pub fn parse(spec: &str) -> Result<Colour, String> {
+        let (bright, name) = match spec.strip_prefix("bright-") {
+            Some(name) => (8, name),
+            None => (0, spec),
+        };
+
+        if let Some(n) = NAMES.iter().position(|c| *c == name) {
+            return Ok(Colour::Basic(n as u8 + bright));
+        }
+
+        if let Some(hex) = spec.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(Colour::Rgb(r, g, b)),
+                _ => Err(format!("colour '{}' should look like #rrggbb", spec)),
+            };
+        }
+
+        spec.parse::<u8>()
+            .map(Colour::Indexed)
+            .map_err(|_| format!("unknown colour '{}', expected a name like red or bright-blue, 0 to 255, or #rrggbb", spec))
+    }
+}
+
+impl Style {
+    fn new(bold: bool, colour: Colour) -> Self {
+        Style { bold, colour: Some(colour) }
+    }
+
+    // words separated by spaces, e.g. "bold #ff8700", "bright-green" or "none"
+    pub fn parse(spec: &str) -> Result<Style, String> {
+        let mut style = Style::default();
+
+        for word in spec.split_whitespace() {
+            match word {
+                "bold" => style.bold = true,
+                "none" | "plain" => {}
+                colour => style.colour = Some(Colour::parse(colour)?),
+            }
+        }
+
+        Ok(style)
+    }
+
+    // the SGR parameters for this style, or nothing if it doesn't change anything
+    pub fn sgr(&self, depth: Depth) -> String {
+        let mut parts = vec![];
+
+        if self.bold {
+            parts.push("1".to_owned());
+        }
+
+        if let Some(colour) = self.colour {
+            parts.push(colour.at_depth(depth).code());
+        }
+
+        parts.join(";")
+    }
+}
+
+impl Role {
+    pub const ALL: [Role; 12] = [Role::Header, Role::Name, Role::Family, Role::Sureness, Role::Error, Role::Gutter,
+                                 Role::Key, Role::String, Role::Number, Role::Boolean, Role::Null, Role::Magic];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Header => "header",
+            Role::Name => "name",
+            Role::Family => "family",
+            Role::Sureness => "sureness",
+            Role::Error => "error",
+            Role::Gutter => "gutter",
+            Role::Key => "key",
+            Role::String => "string",
+            Role::Number => "number",
+            Role::Boolean => "boolean",
+            Role::Null => "null",
+            Role::Magic => "magic",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Role> {
+        Role::ALL.iter().cloned().find(|r| r.name() == name)
+    }
+}
+
+impl Theme {
+    fn from_styles(styles: [Style; 12]) -> Self {
+        Theme { styles: Role::ALL.iter().cloned().zip(styles.iter().cloned()).collect() }
+    }
+
+    // basic colours only, avoiding yellow, cyan and white so it reads on light and dark backgrounds
+    pub fn default_theme() -> Self {
+        let basic = |bold, n| Style::new(bold, Colour::Basic(n));
+        Theme::from_styles([Style { bold: true, colour: None }, basic(true, 4), basic(false, 5), basic(false, 2),
+                            basic(true, 1), basic(true, 4), basic(false, 4), basic(false, 2), basic(false, 5),
+                            basic(false, 1), basic(true, 5), basic(true, 2)])
+    }
+
+    // darker shades for light backgrounds
+    pub fn light() -> Self {
+        let shade = |bold, n| Style::new(bold, Colour::Indexed(n));
+        Theme::from_styles([Style { bold: true, colour: None }, shade(true, 25), shade(false, 90), shade(false, 28),
+                            shade(true, 160), shade(true, 25), shade(false, 25), shade(false, 28), shade(false, 130),
+                            shade(false, 90), shade(false, 30), shade(true, 130)])
+    }
+
+    // lighter shades for dark backgrounds
+    pub fn dark() -> Self {
+        let shade = |bold, n| Style::new(bold, Colour::Indexed(n));
+        Theme::from_styles([Style { bold: true, colour: None }, shade(true, 81), shade(false, 177), shade(false, 150),
+                            shade(true, 203), shade(true, 75), shade(false, 75), shade(false, 114), shade(false, 215),
+                            shade(false, 176), shade(false, 80), shade(true, 214)])
+    }
+
+    pub fn built_in(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Theme::default_theme()),
+            "light" => Some(Theme::light()),
+            "dark" => Some(Theme::dark()),
+            _ => None,
+        }
+    }
+
+    pub fn style(&self, role: Role) -> Style {
+        self.styles.get(&role).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, role: Role, style: Style) {
+        self.styles.insert(role, style);
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::default_theme()
+    }
+}
+
+// COLORTERM is how terminals own up to truecolour, TERM usually says if 256 colours work
+pub fn detect_depth(colorterm: Option<&str>, term: Option<&str>) -> Depth {
+    match (colorterm, term) {
+        (Some("truecolor"), _) | (Some("24bit"), _) => Depth::TrueColour,
+        (_, Some(t)) if t.contains("256") => Depth::TwoFiftySix,
+        _ => Depth::Sixteen,
+    }
+}
+
+pub fn parse_depth(arg: &str) -> Result<Option<Depth>, String> {
+    match arg {
+        "16" => Ok(Some(Depth::Sixteen)),
+        "256" => Ok(Some(Depth::TwoFiftySix)),
+        "truecolour" | "truecolor" | "24bit" => Ok(Some(Depth::TrueColour)),
+        "auto" => Ok(None),
+        _ => Err(format!("colours must be '16', '256', 'truecolour' or 'auto', got '{}'", arg)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_parse() {
+        assert_eq!(Ok(Colour::Basic(1)), Colour::parse("red"));
+        assert_eq!(Ok(Colour::Basic(12)), Colour::parse("bright-blue"));
+        assert_eq!(Ok(Colour::Indexed(214)), Colour::parse("214"));
+        assert_eq!(Ok(Colour::Rgb(255, 135, 0)), Colour::parse("#ff8700"));
+        assert!(Colour::parse("#ff87").is_err());
+        assert!(Colour::parse("puce").is_err());
+        assert!(Colour::parse("256").is_err());
+    }
+
+    #[test]
+    fn styles_parse() {
+        assert_eq!(Ok(Style { bold: true, colour: Some(Colour::Basic(2)) }), Style::parse("bold green"));
+        assert_eq!(Ok(Style::default()), Style::parse("none"));
+        assert!(Style::parse("bold puce").is_err());
+    }
+
+    #[test]
+    fn colours_fall_back() {
+        let orange = Style::parse("bold #ff8700").expect("bad test config");
+        assert_eq!("1;38;2;255;135;0", orange.sgr(Depth::TrueColour));
+        assert_eq!("1;38;5;208", orange.sgr(Depth::TwoFiftySix));
+        assert_eq!("1;33", orange.sgr(Depth::Sixteen));
+
+        assert_eq!("34", Style::new(false, Colour::Indexed(25)).sgr(Depth::Sixteen));
+        assert_eq!("32", Style::new(false, Colour::Basic(2)).sgr(Depth::TrueColour));
+        assert_eq!("", Style::default().sgr(Depth::TrueColour));
+    }
+
+    #[test]
+    fn built_in_themes() {
+        for name in BUILT_IN.iter() {
+            let theme = Theme::built_in(name).expect("should be built in");
+            assert!(Role::ALL.iter().all(|r| theme.style(*r) != Style::default()));
+        }
+        assert_eq!(None, Theme::built_in("neon"));
+    }
+
+    #[test]
+    fn role_names() {
+        for role in Role::ALL.iter() {
+            assert_eq!(Some(*role), Role::from_name(role.name()));
+        }
+        assert_eq!(None, Role::from_name("keys"));
+    }
+
+    #[test]
+    fn depths() {
+        assert_eq!(Depth::TrueColour, detect_depth(Some("truecolor"), Some("xterm-256color")));
+        assert_eq!(Depth::TwoFiftySix, detect_depth(None, Some("xterm-256color")));
+        assert_eq!(Depth::Sixteen, detect_depth(None, Some("xterm")));
+        assert_eq!(Depth::Sixteen, detect_depth(None, None));
+
+        assert_eq!(Ok(Some(Depth::TwoFiftySix)), parse_depth("256"));
+        assert_eq!(Ok(None), parse_depth("auto"));
+        assert!(parse_depth("lots").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+// whether a document is well-formed, and enough of its structure to lay it out again
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use roxmltree::{self, Document, ParsingOptions};
+use report_card::{self, ParseError};
+
+// text and attribute values are kept as written, entities and all
+#[derive(Debug, PartialEq, Clone)]
+pub enum Node {
+    Element { name: String, attributes: Vec<(String, String)>, children: Vec<Node> },
+    Text(String),
+    CData(String),
+    Comment(String),
+    // <?xml ...?> and any other processing instructions, as written
+    Instruction(String),
+    Doctype(String),
+}
+
+// roxmltree's parser recurses on nesting, as does the layout here, so deeper documents are refused
+const MAX_DEPTH: usize = 128;
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn error_at(message: &str, input: &str, offset: u64) -> ParseError {
+    let (line, col) = report_card::position_at(input, offset as usize);
+    ParseError::new(message, line, col)
+}
+
+// roxmltree puts the position in its message, which is shown separately here
+fn document_error(error: &roxmltree::Error) -> ParseError {
+    let pos = error.pos();
+    ParseError::new(&error.to_string().replace(&format!(" at {}", pos), ""), pos.row, pos.col)
+}
+
+// anything malformed is left for roxmltree to report
+fn attributes(start: &BytesStart) -> Vec<(String, String)> {
+    start.attributes()
+         .with_checks(false)
+         .filter_map(|attribute| attribute.ok())
+         .map(|attribute| (lossy(attribute.key.as_ref()), lossy(&attribute.value)))
+         .collect()
+}
+
+enum Unreadable {
+    TooDeep(ParseError),
+    Malformed(ParseError),
+}
+
+// the nodes as written, read without recursion so that depth can be checked before roxmltree sees them
+fn nodes(input: &str) -> Result<Vec<Node>, Unreadable> {
+    let mut reader = Reader::from_str(input);
+    reader.config_mut().check_end_names = false;
+    let mut nodes = vec![];
+    // elements open around the current position, with their children so far
+    let mut open: Vec<Node> = vec![];
+
+    loop {
+        let start = reader.buffer_position();
+
+        let node = match reader.read_event() {
+            Ok(Event::Start(ref tag)) => {
+                if open.len() >= MAX_DEPTH {
+                    return Err(Unreadable::TooDeep(error_at("elements are nested too deeply", input, start)));
+                }
+                open.push(Node::Element { name: lossy(tag.name().as_ref()), attributes: attributes(tag), children: vec![] });
+                continue;
+            }
+            Ok(Event::End(_)) => match open.pop() {
+                Some(element) => element,
+                None => continue,
+            },
+            Ok(Event::Empty(ref tag)) => Node::Element { name: lossy(tag.name().as_ref()), attributes: attributes(tag), children: vec![] },
+            Ok(Event::Text(ref text)) => Node::Text(lossy(text)),
+            Ok(Event::CData(ref data)) => Node::CData(lossy(data)),
+            Ok(Event::Comment(ref comment)) => Node::Comment(lossy(comment)),
+            Ok(Event::Decl(ref declaration)) => Node::Instruction(lossy(declaration)),
+            Ok(Event::PI(ref instruction)) => Node::Instruction(lossy(instruction)),
+            Ok(Event::DocType(ref doctype)) => Node::Doctype(lossy(doctype).trim().to_owned()),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Unreadable::Malformed(error_at(&e.to_string(), input, reader.error_position()))),
+        };
+
+        match open.last_mut() {
+            Some(&mut Node::Element { ref mut children, .. }) => children.push(node),
+            // whitespace between the root and anything around it was only ever layout
+            _ => match node {
+                Node::Text(ref text) if text.trim().is_empty() => {}
+                _ => nodes.push(node),
+            },
+        }
+    }
+
+    Ok(nodes)
+}
+
+// a well-formed document according to roxmltree, as laid out by quick-xml
+pub fn parse(input: &str) -> Result<Vec<Node>, ParseError> {
+    let input = input.trim_start_matches('\u{feff}');
+    let nodes = match nodes(input) {
+        Err(Unreadable::TooDeep(error)) => return Err(error),
+        Err(Unreadable::Malformed(error)) => Err(error),
+        Ok(nodes) => Ok(nodes),
+    };
+
+    // quick-xml stops at the first thing it can't read, so roxmltree gets no deeper, and explains it better
+    let options = ParsingOptions { allow_dtd: true, ..ParsingOptions::default() };
+    Document::parse_with_options(input, options).map_err(|e| document_error(&e))?;
+
+    nodes
+}
+
+pub fn root(nodes: &[Node]) -> Option<&Node> {
+    nodes.iter().find(|n| matches!(n, Node::Element { .. }))
+}
+
+// prefix and URI of every xmlns attribute on the root, with an empty prefix for the default namespace
+pub fn namespaces(nodes: &[Node]) -> Vec<(String, String)> {
+    match root(nodes) {
+        Some(Node::Element { attributes, .. }) => {
+            attributes.iter()
+                      .filter_map(|(name, value)| {
+                          if name == "xmlns" {
+                              Some((String::new(), value.clone()))
+                          } else {
+                              name.strip_prefix("xmlns:").map(|prefix| (prefix.to_owned(), value.clone()))
+                          }
+                      })
+                      .collect()
+        }
+        _ => vec![],
+    }
+}
+
+fn open_tag(name: &str, attributes: &[(String, String)], empty: bool) -> String {
+    let attributes: String = attributes.iter()
+        .map(|(name, value)| {
+            let quote = if value.contains('"') { '\'' } else { '"' };
+            format!(" {}={}{}{}", name, quote, value, quote)
+        })
+        .collect();
+
+    format!("<{}{}{}>", name, attributes, if empty { "/" } else { "" })
+}
+
+fn layout(node: &Node, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+
+    match *node {
+        Node::Element { ref name, ref attributes, ref children } => {
+            // whitespace between tags was only ever layout
+            let children: Vec<&Node> = children.iter()
+                                               .filter(|c| match c { Node::Text(t) => !t.trim().is_empty(), _ => true })
+                                               .collect();
+
+            match children.as_slice() {
+                [] => lines.push(format!("{}{}", indent, open_tag(name, attributes, true))),
+                [Node::Text(text)] if !text.trim().contains('\n') => {
+                    lines.push(format!("{}{}{}</{}>", indent, open_tag(name, attributes, false), text.trim(), name))
+                }
+                _ => {
+                    lines.push(format!("{}{}", indent, open_tag(name, attributes, false)));
+                    for child in children {
+                        layout(child, depth + 1, lines);
+                    }
+                    lines.push(format!("{}</{}>", indent, name));
+                }
+            }
+        }
+        Node::Text(ref text) => {
+            for line in text.trim().lines() {
+                lines.push(format!("{}{}", indent, line.trim()));
+            }
+        }
+        Node::CData(ref data) => lines.push(format!("{}<![CDATA[{}]]>", indent, data)),
+        Node::Comment(ref comment) => lines.push(format!("{}<!--{}-->", indent, comment)),
+        Node::Instruction(ref instruction) => lines.push(format!("{}<?{}?>", indent, instruction)),
+        Node::Doctype(ref doctype) => lines.push(format!("{}<!DOCTYPE {}>", indent, doctype)),
+    }
+}
+
+// re-indented two spaces a level, keeping comments and CDATA exactly as they were
+pub fn pretty(nodes: &[Node]) -> String {
+    let mut lines = vec![];
+
+    for node in nodes {
+        layout(node, 0, &mut lines);
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(name: &str, attributes: Vec<(&str, &str)>, children: Vec<Node>) -> Node {
+        Node::Element {
+            name: name.to_owned(),
+            attributes: attributes.iter().map(|(a, v)| (a.to_string(), v.to_string())).collect(),
+            children,
+        }
+    }
+
+    #[test]
+    fn parses_a_document() {
+        let xml = "<?xml version=\"1.0\"?>\n<!-- hi -->\n<a x='1'><b>t &amp; u</b><![CDATA[<raw>]]><c/></a>";
+        assert_eq!(Ok(vec![Node::Instruction("xml version=\"1.0\"".into()),
+                           Node::Comment(" hi ".into()),
+                           element("a", vec![("x", "1")], vec![element("b", vec![], vec![Node::Text("t &amp; u".into())]),
+                                                               Node::CData("<raw>".into()),
+                                                               element("c", vec![], vec![])])]),
+                   parse(xml));
+    }
+
+    #[test]
+    fn doctypes_can_have_internal_subsets() {
+        let nodes = parse("<!DOCTYPE a [<!ENTITY e \"x\">]><a>&e;</a>").expect("bad test config");
+        assert_eq!(Node::Doctype("a [<!ENTITY e \"x\">]".into()), nodes[0]);
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        assert_eq!(Err(ParseError::new("expected 'b' tag, not 'a'", 2, 6)), parse("<a>\n  <b></a>"));
+        assert_eq!(Err(ParseError::new("expected a quote not '1'", 1, 6)), parse("<a x=1/>"));
+        assert_eq!(Err(ParseError::new("attribute 'x' is already defined", 1, 10)), parse("<a x='1' x='2'/>"));
+        assert_eq!(Err(ParseError::new("malformed entity reference", 1, 6)), parse("<a>b & c</a>"));
+        assert_eq!(Err(ParseError::new("unknown token", 1, 5)), parse("<a/><b/>"));
+        assert_eq!(Err(ParseError::new("unknown token", 1, 1)), parse("hello <a/>"));
+        assert_eq!(Err(ParseError::new("expected '-->'", 1, 17)), parse("<a><!-- oops</a>"));
+        assert_eq!(Err(ParseError::new("the document does not have a root node", 1, 1)), parse("<!-- just a comment -->"));
+    }
+
+    #[test]
+    fn entities_and_prefixes_are_declared() {
+        assert_eq!(Err(ParseError::new("unknown entity reference 'unknown'", 1, 4)), parse("<a>&unknown;</a>"));
+        assert_eq!(Err(ParseError::new("an unknown namespace prefix 'c'", 1, 2)), parse("<c:d/>"));
+        assert!(parse("<a xmlns:c='urn:c'><c:d/></a>").is_ok());
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let deep = format!("{}{}", "<a>".repeat(MAX_DEPTH), "</a>".repeat(MAX_DEPTH));
+        assert!(parse(&deep).is_ok());
+
+        let deeper = "<a>".repeat(2000);
+        assert_eq!(Err(ParseError::new("elements are nested too deeply", 1, 385)), parse(&deeper));
+    }
+
+    #[test]
+    fn root_namespaces() {
+        let nodes = parse("<s:Envelope xmlns:s='urn:s' xmlns='urn:d' id='1'><Body xmlns:x='urn:x'/></s:Envelope>")
+            .expect("bad test config");
+        assert_eq!(vec![("s".to_owned(), "urn:s".to_owned()), ("".to_owned(), "urn:d".to_owned())], namespaces(&nodes));
+    }
+
+    #[test]
+    fn pretty_reindents() {
+        let xml = "<?xml version=\"1.0\"?><a x=\"1\">  <b>text</b><!-- note --><c><d/></c>\n<![CDATA[ raw ]]></a>";
+        let expected = "<?xml version=\"1.0\"?>\n\
+                        <a x=\"1\">\n  \
+                          <b>text</b>\n  \
+                          <!-- note -->\n  \
+                          <c>\n    \
+                            <d/>\n  \
+                          </c>\n  \
+                          <![CDATA[ raw ]]>\n\
+                        </a>";
+        assert_eq!(expected, pretty(&parse(xml).expect("bad test config")));
+    }
+}
@@ -0,0 +1,172 @@
+// a stream of YAML documents, read by serde_yaml and written out again in block style
+
+use report_card::ParseError;
+use serde::Deserialize;
+use serde_yaml::{self, Deserializer, Value};
+
+// serde_yaml puts the position in its message, which is shown separately here
+fn parse_error(error: &serde_yaml::Error) -> ParseError {
+    match error.location() {
+        Some(location) => {
+            let at = format!(" at line {} column {}", location.line(), location.column());
+            ParseError::new(&error.to_string().replacen(&at, "", 1), location.line() as u32, location.column() as u32)
+        }
+        None => ParseError::new(&error.to_string(), 0, 0),
+    }
+}
+
+// every document in the stream, split at --- and ...
+pub fn parse(input: &str) -> Result<Vec<Value>, ParseError> {
+    Deserializer::from_str(input).map(Value::deserialize)
+                                 .collect::<Result<Vec<Value>, serde_yaml::Error>>()
+                                 .map_err(|e| parse_error(&e))
+}
+
+// what a tag is attached to
+pub fn untagged(value: &Value) -> &Value {
+    match *value {
+        Value::Tagged(ref tagged) => untagged(&tagged.value),
+        _ => value,
+    }
+}
+
+pub fn is_collection(value: &Value) -> bool {
+    matches!(untagged(value), Value::Sequence(_) | Value::Mapping(_))
+}
+
+pub fn children(value: &Value) -> Vec<&Value> {
+    match *untagged(value) {
+        Value::Sequence(ref items) => items.iter().collect(),
+        Value::Mapping(ref entries) => entries.values().collect(),
+        _ => vec![],
+    }
+}
+
+// tags anywhere in the tree, other than the core ones that serde_yaml resolves
+pub fn has_tags(value: &Value) -> bool {
+    matches!(value, Value::Tagged(_)) || children(value).iter().any(|c| has_tags(c))
+}
+
+// key and the rest of the line, split at the first ": " outside quotes and brackets
+pub fn split_key(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    let mut depth = 0;
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    for (n, &(i, c)) in chars.iter().enumerate() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && n == 0 => quote = Some(c),
+            None if c == '[' || c == '{' => depth += 1,
+            None if c == ']' || c == '}' => depth -= 1,
+            None if c == ':' && depth == 0 => {
+                let followed_by_space = chars.get(n + 1).map(|&(_, next)| next == ' ').unwrap_or(true);
+                if followed_by_space && i > 0 {
+                    return Some((text[..i].trim_end(), text[i + 1..].trim_start()));
+                }
+            }
+            None => {}
+        }
+    }
+
+    None
+}
+
+// block style, two spaces a level, with --- between documents when there's more than one
+pub fn pretty(documents: &[Value]) -> String {
+    documents.iter()
+             .map(|document| serde_yaml::to_string(document).unwrap_or_default().trim_end().to_owned())
+             .map(|document| if documents.len() > 1 { format!("---\n{}", document) } else { document })
+             .collect::<Vec<String>>()
+             .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(text: &str) -> Value {
+        serde_yaml::from_str(text).expect("bad test config")
+    }
+
+    #[test]
+    fn parses_block_collections() {
+        let documents = parse("# config\nname: scrutey\ntags:\n- a\n- 'b c'\nnested:\n  deep: {x: 1, y: [2, 3]}\n").expect("bad test config");
+        assert_eq!(vec![yaml("{name: scrutey, tags: [a, b c], nested: {deep: {x: 1, y: [2, 3]}}}")], documents);
+        assert!(is_collection(&documents[0]));
+        assert_eq!(3, children(&documents[0]).len());
+    }
+
+    #[test]
+    fn multi_line_quoted_scalars() {
+        assert_eq!(Ok(vec![yaml("{a: multi line quoted, b: 2}")]), parse("a: \"multi\n  line quoted\"\nb: 2"));
+        assert_eq!(Ok(vec![yaml("{a: it's folded}")]), parse("a: 'it''s\n  folded'"));
+    }
+
+    #[test]
+    fn explicit_keys() {
+        assert_eq!(Ok(vec![yaml("{complex key: value}")]), parse("? complex key\n: value"));
+        assert_eq!("? - a\n  - b\n: c", pretty(&parse("? [a, b]\n: c").expect("bad test config")));
+    }
+
+    #[test]
+    fn multiple_documents() {
+        let documents = parse("%YAML 1.2\n---\na: 1\n---\nb: 2\n...\n").expect("bad test config");
+        assert_eq!(vec![yaml("a: 1"), yaml("b: 2")], documents);
+        assert_eq!(Ok(vec![Value::Null]), parse("---\n"));
+    }
+
+    #[test]
+    fn tags() {
+        assert!(has_tags(&yaml("base: !thing\n  x: 1")));
+        assert!(!has_tags(&yaml("date: !!str 2001-12-14")));
+        assert!(is_collection(&yaml("!thing {x: 1}")));
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        assert_eq!(Err(ParseError::new("mapping values are not allowed in this context", 3, 6)), parse("a:\n  b: 1\n    c: 2\n"));
+        assert_eq!(Err(ParseError::new("duplicate entry with key \"a\"", 1, 1)), parse("a: 1\na: 2"));
+        assert_eq!(Err(ParseError::new("unknown anchor", 1, 4)), parse("a: *nope"));
+        assert_eq!(Err(ParseError::new("found character that cannot start any token, while scanning for the next token", 2, 1)),
+                   parse("a:\n\tb: 1"));
+        assert_eq!(Err(ParseError::new("found unexpected end of stream, while scanning a quoted scalar at line 1 column 4", 1, 6)),
+                   parse("a: \"b"));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        assert!(parse(&"[".repeat(5000)).is_err());
+        assert!(parse(&format!("{}x", "- ".repeat(5000))).is_err());
+        assert!(parse(&format!("{}{}", "[".repeat(100), "]".repeat(100))).is_ok());
+    }
+
+    #[test]
+    fn split_keys() {
+        assert_eq!(Some(("a", "b: c")), split_key("a: b: c"));
+        assert_eq!(Some(("'a: b'", "c")), split_key("'a: b': c"));
+        assert_eq!(None, split_key("http://example.com"));
+    }
+
+    #[test]
+    fn pretty_normalises() {
+        let yaml = "a:   1\nlist:\n- x\n-   k: v\n    j: w\nflow: {b: [1, 2]}\nbase: !t\n    deep: 1\nscript: |-\n    make\n    make test\n";
+        let expected = "a: 1\n\
+                        list:\n\
+                        - x\n\
+                        - k: v\n  \
+                          j: w\n\
+                        flow:\n  \
+                          b:\n  \
+                          - 1\n  \
+                          - 2\n\
+                        base: !t\n  \
+                          deep: 1\n\
+                        script: |-\n  \
+                          make\n  \
+                          make test";
+        assert_eq!(expected, pretty(&parse(yaml).expect("bad test config")));
+        assert_eq!("---\na: 1\n---\nb", pretty(&parse("a: 1\n---\nb\n").expect("bad test config")));
+    }
+}